@@ -0,0 +1,88 @@
+//! Measures `tools/call` throughput as the number of concurrent callers grows.
+//!
+//! Run with `cargo bench -p offeryn-core --bench tool_throughput`. Each call
+//! sleeps for a fixed time to stand in for I/O-bound tool work, so throughput
+//! should scale roughly linearly with concurrency.
+
+use async_trait::async_trait;
+use jsonrpc_core::{Call, Id, MethodCall, Params, Request, Version};
+use offeryn_core::McpServer;
+use offeryn_types::{CallToolResult, McpTool, ToolContext};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const TOOL_LATENCY: Duration = Duration::from_millis(5);
+const CALLS_PER_WORKER: usize = 20;
+
+struct IoBoundTool;
+
+#[async_trait]
+impl McpTool for IoBoundTool {
+    fn name(&self) -> &str {
+        "io_bound"
+    }
+
+    fn description(&self) -> &str {
+        "Sleeps to simulate I/O"
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn execute(&self, _args: Value, _ctx: ToolContext) -> Result<CallToolResult, String> {
+        tokio::time::sleep(TOOL_LATENCY).await;
+        Ok(CallToolResult::text("ok"))
+    }
+}
+
+fn call_request(id: u64) -> Request {
+    let params = json!({ "name": "io_bound", "arguments": {} });
+    Request::Single(Call::MethodCall(MethodCall {
+        jsonrpc: Some(Version::V2),
+        method: "tools/call".to_string(),
+        params: match params {
+            Value::Object(map) => Params::Map(map),
+            _ => unreachable!(),
+        },
+        id: Id::Num(id),
+    }))
+}
+
+async fn run(server: Arc<McpServer>, workers: usize) -> f64 {
+    let start = Instant::now();
+    let handles: Vec<_> = (0..workers)
+        .map(|worker| {
+            let server = server.clone();
+            tokio::spawn(async move {
+                for i in 0..CALLS_PER_WORKER {
+                    let id = (worker * CALLS_PER_WORKER + i) as u64;
+                    server.handle_request(call_request(id)).await.unwrap();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.await.unwrap();
+    }
+    (workers * CALLS_PER_WORKER) as f64 / start.elapsed().as_secs_f64()
+}
+
+#[tokio::main]
+async fn main() {
+    let server = Arc::new(McpServer::new("bench", "1.0.0"));
+    server.register_tool(IoBoundTool).await;
+
+    let baseline = run(server.clone(), 1).await;
+    println!("{:>4} workers: {:>8.0} calls/s (1.0x)", 1, baseline);
+    for workers in [4, 16, 64] {
+        let throughput = run(server.clone(), workers).await;
+        println!(
+            "{:>4} workers: {:>8.0} calls/s ({:.1}x)",
+            workers,
+            throughput,
+            throughput / baseline
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{ClientError, ClientTransport};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Launches an MCP server as a child process and speaks newline-delimited
+/// JSON-RPC over its stdin/stdout. The child is killed when the transport is
+/// dropped.
+pub struct ChildProcessTransport {
+    child: std::sync::Mutex<Child>,
+    stdin: Mutex<BufWriter<ChildStdin>>,
+    stdout: Mutex<BufReader<ChildStdout>>,
+    stderr_task: JoinHandle<()>,
+}
+
+impl ChildProcessTransport {
+    /// Spawn `command`, taking over its standard streams.
+    pub fn spawn(mut command: Command) -> Result<Self, ClientError> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| ClientError::Transport(format!("Failed to spawn server: {}", e)))?;
+
+        let pid = child.id();
+        info!(pid = ?pid, "Spawned server process");
+
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let stderr = child.stderr.take().expect("stderr is piped");
+
+        let stderr_task = tokio::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                info!(pid = ?pid, line = %line, "Server stderr");
+            }
+        });
+
+        Ok(Self {
+            child: std::sync::Mutex::new(child),
+            stdin: Mutex::new(BufWriter::new(stdin)),
+            stdout: Mutex::new(BufReader::new(stdout)),
+            stderr_task,
+        })
+    }
+
+    /// OS process id of the server, if it is still running.
+    pub fn id(&self) -> Option<u32> {
+        self.child.lock().unwrap().id()
+    }
+}
+
+#[async_trait]
+impl ClientTransport for ChildProcessTransport {
+    async fn send(&self, message: Value) -> Result<(), ClientError> {
+        let mut bytes = serde_json::to_vec(&message)?;
+        bytes.push(b'\n');
+
+        let mut stdin = self.stdin.lock().await;
+        stdin
+            .write_all(&bytes)
+            .await
+            .map_err(|e| ClientError::Transport(e.to_string()))?;
+        stdin
+            .flush()
+            .await
+            .map_err(|e| ClientError::Transport(e.to_string()))
+    }
+
+    async fn receive(&self) -> Result<Option<Value>, ClientError> {
+        let mut stdout = self.stdout.lock().await;
+        loop {
+            let mut line = String::new();
+            let n = stdout
+                .read_line(&mut line)
+                .await
+                .map_err(|e| ClientError::Transport(e.to_string()))?;
+            if n == 0 {
+                debug!("Server closed stdout");
+                return Ok(None);
+            }
+
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            match serde_json::from_str(line) {
+                Ok(message) => return Ok(Some(message)),
+                Err(e) => warn!(error = %e, line = %line, "Ignoring non-JSON output from server"),
+            }
+        }
+    }
+}
+
+impl Drop for ChildProcessTransport {
+    fn drop(&mut self) {
+        self.stderr_task.abort();
+        let mut child = self.child.lock().unwrap();
+        if let Err(e) = child.start_kill() {
+            debug!(error = %e, "Server process already exited");
+        }
+        // Reap immediately if possible; otherwise tokio reaps the orphan in the background.
+        let _ = child.try_wait();
+    }
+}
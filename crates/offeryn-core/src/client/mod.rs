@@ -0,0 +1,259 @@
+mod child_process;
+mod sse;
+
+pub use child_process::ChildProcessTransport;
+pub use sse::SseClientTransport;
+
+use crate::ClientError;
+use async_trait::async_trait;
+use jsonrpc_core::{
+    Call, Failure, Id, MethodCall, Notification, Output, Params, Request as JsonRpcRequest,
+    Success, Version,
+};
+use offeryn_types::*;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// A bidirectional channel to an MCP server carrying raw JSON-RPC messages.
+#[async_trait]
+pub trait ClientTransport: Send + Sync + 'static {
+    async fn send(&self, message: Value) -> Result<(), ClientError>;
+
+    /// Wait for the next message from the server. Returns `None` once the
+    /// connection is closed.
+    async fn receive(&self) -> Result<Option<Value>, ClientError>;
+}
+
+/// Requests awaiting a response. `closed` is set under the same lock once the
+/// reader stops, so a request can't be registered after nobody will answer it.
+#[derive(Default)]
+struct Pending {
+    requests: HashMap<Id, oneshot::Sender<Output>>,
+    closed: bool,
+}
+
+type PendingRequests = Arc<Mutex<Pending>>;
+
+pub struct McpClient {
+    transport: Arc<dyn ClientTransport>,
+    next_id: AtomicU64,
+    pending: PendingRequests,
+    reader: JoinHandle<()>,
+}
+
+impl McpClient {
+    pub fn new(transport: impl ClientTransport) -> Self {
+        let transport: Arc<dyn ClientTransport> = Arc::new(transport);
+        let pending = PendingRequests::default();
+        let reader = tokio::spawn(Self::read_loop(transport.clone(), pending.clone()));
+        Self {
+            transport,
+            next_id: AtomicU64::new(1),
+            pending,
+            reader,
+        }
+    }
+
+    async fn read_loop(transport: Arc<dyn ClientTransport>, pending: PendingRequests) {
+        loop {
+            let message = match transport.receive().await {
+                Ok(Some(message)) => message,
+                Ok(None) => {
+                    info!("Server connection closed");
+                    break;
+                }
+                Err(e) => {
+                    warn!(error = %e, "Failed to receive message from server");
+                    break;
+                }
+            };
+
+            if message.get("method").is_some() {
+                Self::handle_server_message(&transport, message).await;
+                continue;
+            }
+
+            match serde_json::from_value::<Output>(message) {
+                Ok(output) => {
+                    let sender = pending.lock().unwrap().requests.remove(output.id());
+                    match sender {
+                        Some(sender) => {
+                            let _ = sender.send(output);
+                        }
+                        None => debug!(id = ?output.id(), "Dropping response to unknown request"),
+                    }
+                }
+                Err(e) => warn!(error = %e, "Received malformed response from server"),
+            }
+        }
+
+        // Fail anything still waiting so callers see the connection drop.
+        let mut pending = pending.lock().unwrap();
+        pending.closed = true;
+        pending.requests.clear();
+    }
+
+    async fn handle_server_message(transport: &Arc<dyn ClientTransport>, message: Value) {
+        match serde_json::from_value::<Call>(message) {
+            Ok(Call::MethodCall(call)) => {
+                let output = if call.method == "ping" {
+                    Output::Success(Success {
+                        jsonrpc: Some(Version::V2),
+                        result: Value::Object(Default::default()),
+                        id: call.id,
+                    })
+                } else {
+                    debug!(method = %call.method, "Rejecting unsupported server request");
+                    Output::Failure(Failure {
+                        jsonrpc: Some(Version::V2),
+                        error: JsonRpcError::method_not_found(),
+                        id: call.id,
+                    })
+                };
+                if let Ok(output) = serde_json::to_value(output) {
+                    if let Err(e) = transport.send(output).await {
+                        warn!(error = %e, "Failed to reply to server request");
+                    }
+                }
+            }
+            Ok(Call::Notification(notification)) => {
+                debug!(method = %notification.method, "Received server notification");
+            }
+            _ => warn!("Received invalid message from server"),
+        }
+    }
+
+    /// Send a request and wait for its result.
+    pub async fn request<R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<R, ClientError> {
+        let params = to_params(params)?;
+        let id = Id::Num(self.next_id.fetch_add(1, Ordering::SeqCst));
+        let (tx, rx) = oneshot::channel();
+        {
+            let mut pending = self.pending.lock().unwrap();
+            if pending.closed {
+                return Err(ClientError::ConnectionClosed);
+            }
+            pending.requests.insert(id.clone(), tx);
+        }
+
+        let request = JsonRpcRequest::Single(Call::MethodCall(MethodCall {
+            jsonrpc: Some(Version::V2),
+            method: method.to_string(),
+            params,
+            id: id.clone(),
+        }));
+
+        debug!(method = %method, id = ?id, "Sending request");
+        if let Err(e) = self.transport.send(serde_json::to_value(request)?).await {
+            self.pending.lock().unwrap().requests.remove(&id);
+            return Err(e);
+        }
+
+        match rx.await.map_err(|_| ClientError::ConnectionClosed)? {
+            Output::Success(success) => Ok(serde_json::from_value(success.result)?),
+            Output::Failure(failure) => Err(ClientError::Rpc(failure.error)),
+        }
+    }
+
+    pub async fn notify(&self, method: &str, params: Value) -> Result<(), ClientError> {
+        let notification = JsonRpcRequest::Single(Call::Notification(Notification {
+            jsonrpc: Some(Version::V2),
+            method: method.to_string(),
+            params: to_params(params)?,
+        }));
+        debug!(method = %method, "Sending notification");
+        self.transport
+            .send(serde_json::to_value(notification)?)
+            .await
+    }
+
+    /// Perform the `initialize` / `notifications/initialized` handshake.
+    pub async fn initialize(
+        &self,
+        client_info: Implementation,
+    ) -> Result<InitializeResult, ClientError> {
+        let params = InitializeParams {
+            protocol_version: LATEST_PROTOCOL_VERSION.to_string(),
+            capabilities: ClientCapabilities {
+                experimental: HashMap::new(),
+                sampling: None,
+                roots: None,
+            },
+            client_info,
+        };
+        let result: InitializeResult = self
+            .request("initialize", serde_json::to_value(params)?)
+            .await?;
+
+        if !SUPPORTED_PROTOCOL_VERSIONS.contains(&result.protocol_version.as_str()) {
+            warn!(
+                protocol_version = %result.protocol_version,
+                "Server chose an unsupported protocol version"
+            );
+            return Err(ClientError::UnsupportedProtocolVersion(
+                result.protocol_version,
+            ));
+        }
+
+        info!(
+            server_name = %result.server_info.name,
+            server_version = %result.server_info.version,
+            protocol_version = %result.protocol_version,
+            "Initialized connection to server"
+        );
+
+        self.notify("notifications/initialized", Value::Null)
+            .await?;
+        Ok(result)
+    }
+
+    pub async fn list_tools(&self) -> Result<ListToolsResult, ClientError> {
+        self.request("tools/list", Value::Null).await
+    }
+
+    pub async fn call_tool(&self, name: &str, args: Value) -> Result<CallToolResult, ClientError> {
+        let arguments = match args {
+            Value::Object(map) => Some(map.into_iter().collect()),
+            Value::Null => None,
+            _ => {
+                return Err(ClientError::InvalidRequest(
+                    "Tool arguments must be a JSON object".to_string(),
+                ))
+            }
+        };
+        let request = CallToolRequest {
+            name: name.to_string(),
+            arguments,
+            meta: None,
+        };
+        self.request("tools/call", serde_json::to_value(request)?)
+            .await
+    }
+}
+
+impl Drop for McpClient {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+fn to_params(params: Value) -> Result<Params, ClientError> {
+    match params {
+        Value::Null => Ok(Params::None),
+        Value::Object(map) => Ok(Params::Map(map)),
+        Value::Array(values) => Ok(Params::Array(values)),
+        _ => Err(ClientError::InvalidRequest(
+            "Request params must be an object or array".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{ClientError, ClientTransport};
+use async_trait::async_trait;
+use futures::StreamExt;
+use reqwest::Url;
+use serde_json::Value;
+use tokio::sync::{mpsc, oneshot, Mutex};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Connects to an [`SseTransport`](crate::transport::SseTransport)-style server:
+/// listens on the `/sse` event stream and posts messages to the endpoint the
+/// server advertises in its initial `endpoint` event.
+pub struct SseClientTransport {
+    http: reqwest::Client,
+    endpoint: Url,
+    incoming: Mutex<mpsc::Receiver<Value>>,
+    reader: JoinHandle<()>,
+}
+
+impl SseClientTransport {
+    /// Open the event stream at `url` (e.g. `http://localhost:3000/sse`) and
+    /// wait for the server to announce its message endpoint.
+    pub async fn connect(url: &str) -> Result<Self, ClientError> {
+        let url = Url::parse(url).map_err(|e| ClientError::Transport(e.to_string()))?;
+        let http = reqwest::Client::new();
+
+        let response = http
+            .get(url.clone())
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| ClientError::Transport(e.to_string()))?;
+        info!(url = %url, "Connected to SSE stream");
+
+        let (endpoint_tx, endpoint_rx) = oneshot::channel();
+        let (tx, rx) = mpsc::channel(100);
+        let reader = tokio::spawn(Self::read_events(response, endpoint_tx, tx));
+
+        let endpoint = endpoint_rx
+            .await
+            .map_err(|_| ClientError::ConnectionClosed)?;
+        let endpoint = url
+            .join(&endpoint)
+            .map_err(|e| ClientError::Transport(e.to_string()))?;
+        info!(endpoint = %endpoint, "Received message endpoint");
+
+        Ok(Self {
+            http,
+            endpoint,
+            incoming: Mutex::new(rx),
+            reader,
+        })
+    }
+
+    async fn read_events(
+        response: reqwest::Response,
+        endpoint_tx: oneshot::Sender<String>,
+        tx: mpsc::Sender<Value>,
+    ) {
+        let mut endpoint_tx = Some(endpoint_tx);
+        let mut stream = response.bytes_stream();
+        let mut buffer = Vec::new();
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    warn!(error = %e, "SSE stream failed");
+                    break;
+                }
+            };
+            buffer.extend(chunk.iter().filter(|&&b| b != b'\r'));
+
+            while let Some(end) = buffer.windows(2).position(|w| w == b"\n\n") {
+                let block: Vec<u8> = buffer.drain(..end + 2).collect();
+                let Some((event, data)) = parse_event(&String::from_utf8_lossy(&block)) else {
+                    continue;
+                };
+                match event.as_str() {
+                    "endpoint" => {
+                        if let Some(endpoint_tx) = endpoint_tx.take() {
+                            let _ = endpoint_tx.send(data);
+                        }
+                    }
+                    "message" => match serde_json::from_str(&data) {
+                        Ok(message) => {
+                            if tx.send(message).await.is_err() {
+                                return;
+                            }
+                        }
+                        Err(e) => warn!(error = %e, "Ignoring malformed SSE message"),
+                    },
+                    other => debug!(event = %other, "Ignoring unknown SSE event"),
+                }
+            }
+        }
+        info!("SSE stream closed");
+    }
+}
+
+/// Parse one SSE event block into its event name and data payload.
+fn parse_event(block: &str) -> Option<(String, String)> {
+    let mut event = "message".to_string();
+    let mut data = Vec::new();
+    for line in block.lines() {
+        if let Some(value) = line.strip_prefix("event:") {
+            event = value.trim().to_string();
+        } else if let Some(value) = line.strip_prefix("data:") {
+            data.push(value.strip_prefix(' ').unwrap_or(value));
+        }
+    }
+    if data.is_empty() {
+        // Comments and keep-alives carry no data
+        return None;
+    }
+    Some((event, data.join("\n")))
+}
+
+#[async_trait]
+impl ClientTransport for SseClientTransport {
+    async fn send(&self, message: Value) -> Result<(), ClientError> {
+        // Responses are delivered on the event stream, so the POST body is not needed.
+        self.http
+            .post(self.endpoint.clone())
+            .json(&message)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| ClientError::Transport(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn receive(&self) -> Result<Option<Value>, ClientError> {
+        Ok(self.incoming.lock().await.recv().await)
+    }
+}
+
+impl Drop for SseClientTransport {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
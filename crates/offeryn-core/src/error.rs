@@ -6,6 +6,7 @@ pub enum McpError {
     InvalidRequest,
     InvalidParams,
     MethodNotFound,
+    ResourceNotFound,
     InternalError,
 }
 
@@ -15,6 +16,7 @@ impl fmt::Display for McpError {
             McpError::InvalidRequest => write!(f, "Invalid request"),
             McpError::InvalidParams => write!(f, "Invalid parameters"),
             McpError::MethodNotFound => write!(f, "Method not found"),
+            McpError::ResourceNotFound => write!(f, "Resource not found"),
             McpError::InternalError => write!(f, "Internal error"),
         }
     }
@@ -32,7 +34,55 @@ impl From<McpError> for JsonRpcError {
             McpError::InvalidRequest => JsonRpcError::invalid_request(),
             McpError::InvalidParams => JsonRpcError::invalid_params("Invalid parameters"),
             McpError::MethodNotFound => JsonRpcError::method_not_found(),
+            McpError::ResourceNotFound => JsonRpcError {
+                code: ErrorCode::ServerError(-32002),
+                message: "Resource not found".to_string(),
+                data: None,
+            },
             McpError::InternalError => JsonRpcError::new(ErrorCode::ServerError(-32000)),
         }
     }
 }
+
+#[cfg(feature = "client")]
+#[derive(Debug)]
+pub enum ClientError {
+    /// The transport failed to deliver or receive a message
+    Transport(String),
+    /// The server answered with a JSON-RPC error
+    Rpc(JsonRpcError),
+    /// The request was rejected before being sent
+    InvalidRequest(String),
+    /// A message could not be serialized or deserialized
+    Serialization(serde_json::Error),
+    /// The connection closed before a response arrived
+    ConnectionClosed,
+    /// The server chose a protocol version this client does not speak
+    UnsupportedProtocolVersion(String),
+}
+
+#[cfg(feature = "client")]
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Transport(e) => write!(f, "Transport error: {}", e),
+            ClientError::Rpc(e) => write!(f, "Server error {}: {}", e.code.code(), e.message),
+            ClientError::InvalidRequest(e) => write!(f, "Invalid request: {}", e),
+            ClientError::Serialization(e) => write!(f, "Serialization error: {}", e),
+            ClientError::ConnectionClosed => write!(f, "Connection closed"),
+            ClientError::UnsupportedProtocolVersion(version) => {
+                write!(f, "Unsupported protocol version: {}", version)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+impl std::error::Error for ClientError {}
+
+#[cfg(feature = "client")]
+impl From<serde_json::Error> for ClientError {
+    fn from(error: serde_json::Error) -> Self {
+        ClientError::Serialization(error)
+    }
+}
@@ -1,10 +1,17 @@
+#[cfg(feature = "client")]
+pub mod client;
 pub mod error;
 pub mod server;
 pub mod transport;
 
+#[cfg(feature = "client")]
+pub use client::{ChildProcessTransport, ClientTransport, McpClient, SseClientTransport};
+#[cfg(feature = "client")]
+pub use error::ClientError;
 pub use error::McpError;
 pub use offeryn_types::{
-    CallToolRequest, CallToolResult, Content, InitializeResult, ListToolsResult,
-    ServerCapabilities, ServerInfo, Tool, LATEST_PROTOCOL_VERSION,
+    CallToolRequest, CallToolResult, Content, InitializeResult, ListResourceTemplatesResult,
+    ListResourcesResult, ListToolsResult, ReadResourceResult, ServerCapabilities, ServerInfo, Tool,
+    LATEST_PROTOCOL_VERSION,
 };
-pub use server::McpServer;
+pub use server::{McpLoggingLayer, McpServer, Session};
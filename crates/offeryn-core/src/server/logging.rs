@@ -0,0 +1,240 @@
+use super::McpServer;
+use jsonrpc_core::Id;
+use offeryn_types::LoggingLevel;
+use serde_json::{Map, Value};
+use std::fmt;
+use std::sync::{Arc, Weak};
+use tracing::field::{Field, Visit};
+use tracing::{span, Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Name of the span the server opens around every request it handles.
+pub(crate) const REQUEST_SPAN: &str = "mcp_request";
+
+/// A [`Layer`] that forwards `tracing` events emitted while handling a request
+/// to the client that sent it, as `notifications/message`.
+///
+/// Only events at or above the level the client chose with `logging/setLevel`
+/// are sent. Events from offeryn itself are never forwarded, so delivering a
+/// message cannot produce further messages. Work spawned onto other tasks
+/// leaves the request span and is not forwarded either.
+pub struct McpLoggingLayer {
+    server: Weak<McpServer>,
+}
+
+impl McpLoggingLayer {
+    pub fn new(server: &Arc<McpServer>) -> Self {
+        Self {
+            server: Arc::downgrade(server),
+        }
+    }
+}
+
+/// Session and request ids stored in the extensions of a request span.
+struct RequestScope {
+    session_id: String,
+    request_id: Option<Id>,
+}
+
+impl<S> Layer<S> for McpLoggingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != REQUEST_SPAN {
+            return;
+        }
+        let mut visitor = FieldVisitor::default();
+        attrs.record(&mut visitor);
+        if let (Some(Value::String(session_id)), Some(span)) =
+            (visitor.fields.remove("session_id"), ctx.span(id))
+        {
+            let request_id = match visitor.fields.remove("request_id") {
+                Some(Value::String(request_id)) => serde_json::from_str(&request_id).ok(),
+                _ => None,
+            };
+            span.extensions_mut().insert(RequestScope {
+                session_id,
+                request_id,
+            });
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        if metadata.target().starts_with(env!("CARGO_CRATE_NAME")) {
+            return;
+        }
+
+        let Some((session_id, request_id)) = ctx.event_scope(event).and_then(|scope| {
+            scope.into_iter().find_map(|span| {
+                span.extensions()
+                    .get::<RequestScope>()
+                    .map(|scope| (scope.session_id.clone(), scope.request_id.clone()))
+            })
+        }) else {
+            return;
+        };
+        let Some(session) = self
+            .server
+            .upgrade()
+            .and_then(|server| server.session(&session_id))
+        else {
+            return;
+        };
+
+        let level = logging_level(metadata.level());
+        if level < session.log_level() {
+            return;
+        }
+
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let data = match visitor.fields.len() {
+            1 if visitor.fields.contains_key("message") => {
+                visitor.fields.remove("message").unwrap()
+            }
+            _ => Value::Object(visitor.fields),
+        };
+
+        // Tracing callbacks are synchronous, so drop the message rather than block
+        let _ = session.try_log(request_id.as_ref(), level, Some(metadata.target()), data);
+    }
+}
+
+fn logging_level(level: &Level) -> LoggingLevel {
+    match *level {
+        Level::ERROR => LoggingLevel::Error,
+        Level::WARN => LoggingLevel::Warning,
+        Level::INFO => LoggingLevel::Info,
+        _ => LoggingLevel::Debug,
+    }
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    fields: Map<String, Value>,
+}
+
+impl Visit for FieldVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.fields.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.fields.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.fields.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.fields.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.fields.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.fields
+            .insert(field.name().to_string(), format!("{:?}", value).into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use jsonrpc_core::{Call, Id, MethodCall, Params, Request, Version};
+    use offeryn_types::{CallToolResult, LoggingMessageNotification, McpTool, ToolContext};
+    use serde_json::json;
+    use tokio::sync::mpsc;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    struct ChattyTool;
+
+    #[async_trait]
+    impl McpTool for ChattyTool {
+        fn name(&self) -> &str {
+            "chatty"
+        }
+
+        fn description(&self) -> &str {
+            "Logs while it works"
+        }
+
+        fn input_schema(&self) -> Value {
+            json!({ "type": "object" })
+        }
+
+        async fn execute(&self, _args: Value, _ctx: ToolContext) -> Result<CallToolResult, String> {
+            tracing::debug!(target: "chatty", "Too detailed");
+            tracing::warn!(target: "chatty", free_mb = 12, "Disk almost full");
+            Ok(CallToolResult::text("done"))
+        }
+    }
+
+    fn call(method: &str, params: Value) -> Request {
+        Request::Single(Call::MethodCall(MethodCall {
+            jsonrpc: Some(Version::V2),
+            method: method.to_string(),
+            params: match params {
+                Value::Object(map) => Params::Map(map),
+                _ => Params::None,
+            },
+            id: Id::Num(1),
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_forwards_events_to_session() {
+        let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+        server.register_tool(ChattyTool).await;
+        let subscriber = tracing_subscriber::registry().with(McpLoggingLayer::new(&server));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let (tx, mut rx) = mpsc::channel(10);
+        let session = server.connect(tx);
+        server
+            .handle_session_request(&session, call("tools/call", json!({ "name": "chatty" })))
+            .await
+            .unwrap();
+
+        // Only the warning passes the default level, and offeryn's own events are skipped
+        let Some(Call::Notification(notification)) = rx.recv().await else {
+            panic!("Expected log notification");
+        };
+        assert_eq!(notification.method, "notifications/message");
+        let message: LoggingMessageNotification = notification.params.parse().unwrap();
+        assert_eq!(message.level, LoggingLevel::Warning);
+        assert_eq!(message.logger.as_deref(), Some("chatty"));
+        assert_eq!(
+            message.data,
+            json!({ "message": "Disk almost full", "free_mb": 12 })
+        );
+        assert!(rx.try_recv().is_err());
+
+        // Lowering the level lets debug events through
+        server
+            .handle_session_request(
+                &session,
+                call("logging/setLevel", json!({ "level": "debug" })),
+            )
+            .await
+            .unwrap();
+        server
+            .handle_session_request(&session, call("tools/call", json!({ "name": "chatty" })))
+            .await
+            .unwrap();
+        let Some(Call::Notification(notification)) = rx.recv().await else {
+            panic!("Expected log notification");
+        };
+        let message: LoggingMessageNotification = notification.params.parse().unwrap();
+        assert_eq!(message.level, LoggingLevel::Debug);
+        assert_eq!(message.data, json!("Too detailed"));
+    }
+}
@@ -1,17 +1,35 @@
+mod logging;
+mod session;
+mod version;
+
+pub use logging::McpLoggingLayer;
+pub use session::Session;
+
+use session::RequestPeer;
+
 use crate::McpError;
+use futures::future::join_all;
 use jsonrpc_core::{
-    Call, ErrorCode, Failure, Output, Params, Request as JsonRpcRequest,
+    Call, ErrorCode, Failure, MethodCall, Notification, Output, Params, Request as JsonRpcRequest,
     Response as JsonRpcResponse, Success, Version,
 };
 use offeryn_types::*;
 use std::collections::HashMap;
-use tokio::sync::Mutex;
-use tracing::{debug, info, warn};
+use std::sync::{Arc, RwLock};
+use tokio::sync::mpsc;
+use tracing::{debug, info, info_span, warn, Instrument};
 
 pub struct McpServer {
     name: String,
     version: String,
-    tools: Mutex<HashMap<String, Box<dyn McpTool>>>,
+    // Registries are read on every request and rarely written. Handlers clone
+    // the `Arc` out and release the lock before awaiting, so slow tools never
+    // block other calls.
+    tools: RwLock<HashMap<String, Arc<dyn McpTool>>>,
+    resources: RwLock<HashMap<String, Arc<dyn McpResource>>>,
+    resource_templates: RwLock<Vec<Arc<dyn McpResourceTemplate>>>,
+    prompts: RwLock<HashMap<String, Arc<dyn McpPrompt>>>,
+    sessions: RwLock<HashMap<String, Arc<Session>>>,
 }
 
 impl McpServer {
@@ -19,23 +37,30 @@ impl McpServer {
         Self {
             name: name.to_string(),
             version: version.to_string(),
-            tools: Mutex::new(HashMap::new()),
+            tools: RwLock::new(HashMap::new()),
+            resources: RwLock::new(HashMap::new()),
+            resource_templates: RwLock::new(Vec::new()),
+            prompts: RwLock::new(HashMap::new()),
+            sessions: RwLock::new(HashMap::new()),
         }
     }
 
     pub async fn with_tool(&self, tool: impl McpTool + 'static) -> &Self {
         let tool_name = tool.name().to_string();
         info!(tool_name = %tool_name, "Registering tool");
-        self.tools.lock().await.insert(tool_name, Box::new(tool));
+        self.tools
+            .write()
+            .unwrap()
+            .insert(tool_name, Arc::new(tool));
         self
     }
 
     pub async fn with_tools(&self, tools: Vec<Box<dyn McpTool>>) -> &Self {
-        let mut tools_lock = self.tools.lock().await;
+        let mut tools_lock = self.tools.write().unwrap();
         for tool in tools {
             let name = tool.name().to_string();
             info!(tool_name = %name, "Registering tool");
-            tools_lock.insert(name, tool);
+            tools_lock.insert(name, Arc::from(tool));
         }
         self
     }
@@ -43,68 +68,331 @@ impl McpServer {
     pub async fn register_tool<T: McpTool + 'static>(&self, tool: T) {
         let tool_name = tool.name().to_string();
         info!(tool_name = %tool_name, "Registering tool");
-        self.tools.lock().await.insert(tool_name, Box::new(tool));
+        self.tools
+            .write()
+            .unwrap()
+            .insert(tool_name, Arc::new(tool));
     }
 
     pub async fn register_tools<T: HasTools>(&self, provider: T)
     where
         T::Tools: IntoIterator<Item = Box<dyn McpTool>>,
     {
-        let mut tools_lock = self.tools.lock().await;
+        let mut tools_lock = self.tools.write().unwrap();
         for tool in provider.tools() {
             let name = tool.name().to_string();
             info!(tool_name = %name, "Registering tool");
-            tools_lock.insert(name, tool);
+            tools_lock.insert(name, Arc::from(tool));
+        }
+    }
+
+    pub async fn register_resource<R: McpResource + 'static>(&self, resource: R) {
+        let uri = resource.uri().to_string();
+        info!(uri = %uri, "Registering resource");
+        self.resources
+            .write()
+            .unwrap()
+            .insert(uri, Arc::new(resource));
+    }
+
+    pub async fn register_resource_template<R: McpResourceTemplate + 'static>(&self, template: R) {
+        info!(uri_template = %template.uri_template(), "Registering resource template");
+        self.resource_templates
+            .write()
+            .unwrap()
+            .push(Arc::new(template));
+    }
+
+    pub async fn register_resources<R: HasResources>(&self, provider: R)
+    where
+        R::Resources: IntoIterator<Item = ResourceEntry>,
+    {
+        let mut resources_lock = self.resources.write().unwrap();
+        let mut templates_lock = self.resource_templates.write().unwrap();
+        for entry in provider.resources() {
+            match entry {
+                ResourceEntry::Static(resource) => {
+                    let uri = resource.uri().to_string();
+                    info!(uri = %uri, "Registering resource");
+                    resources_lock.insert(uri, Arc::from(resource));
+                }
+                ResourceEntry::Template(template) => {
+                    info!(uri_template = %template.uri_template(), "Registering resource template");
+                    templates_lock.push(Arc::from(template));
+                }
+            }
+        }
+    }
+
+    pub async fn register_prompt<P: McpPrompt + 'static>(&self, prompt: P) {
+        let prompt_name = prompt.name().to_string();
+        info!(prompt_name = %prompt_name, "Registering prompt");
+        self.prompts
+            .write()
+            .unwrap()
+            .insert(prompt_name, Arc::new(prompt));
+    }
+
+    pub async fn register_prompts<P: HasPrompts>(&self, provider: P)
+    where
+        P::Prompts: IntoIterator<Item = Box<dyn McpPrompt>>,
+    {
+        let mut prompts_lock = self.prompts.write().unwrap();
+        for prompt in provider.prompts() {
+            let name = prompt.name().to_string();
+            info!(prompt_name = %name, "Registering prompt");
+            prompts_lock.insert(name, Arc::from(prompt));
         }
     }
 
+    /// Register a new client connection. Server-initiated messages for the
+    /// session are delivered through `sender`.
+    pub fn connect(&self, sender: mpsc::Sender<Call>) -> Arc<Session> {
+        let session = Arc::new(Session::new(sender));
+        let mut sessions = self.sessions.write().unwrap();
+        sessions.insert(session.id().to_string(), session.clone());
+        info!(
+            session_id = %session.id(),
+            active_sessions = %sessions.len(),
+            "Session connected"
+        );
+        session
+    }
+
+    pub fn disconnect(&self, session_id: &str) {
+        let mut sessions = self.sessions.write().unwrap();
+        if let Some(session) = sessions.remove(session_id) {
+            session.close();
+            info!(
+                session_id = %session_id,
+                active_sessions = %sessions.len(),
+                "Session disconnected"
+            );
+        }
+    }
+
+    pub fn session(&self, session_id: &str) -> Option<Arc<Session>> {
+        self.sessions.read().unwrap().get(session_id).cloned()
+    }
+
+    /// Notify every session subscribed to `uri` that the resource has changed.
+    pub async fn notify_resource_updated(&self, uri: &str) {
+        let subscribers: Vec<Arc<Session>> = self
+            .sessions
+            .read()
+            .unwrap()
+            .values()
+            .filter(|session| session.is_subscribed(uri))
+            .cloned()
+            .collect();
+
+        debug!(
+            uri = %uri,
+            num_subscribers = %subscribers.len(),
+            "Sending resource updated notifications"
+        );
+
+        for session in subscribers {
+            let params = serde_json::to_value(ResourceUpdatedNotification {
+                uri: uri.to_string(),
+            })
+            .unwrap_or_default();
+            if let Err(e) = session
+                .notify("notifications/resources/updated", params)
+                .await
+            {
+                warn!(
+                    session_id = %session.id(),
+                    error = %e,
+                    "Failed to send resource updated notification"
+                );
+            }
+        }
+    }
+
+    /// Handle a request that is not associated with any connected session.
     pub async fn handle_request(
         &self,
         request: JsonRpcRequest,
-    ) -> Result<JsonRpcResponse, McpError> {
-        let (id, method, params) = match request {
-            JsonRpcRequest::Single(Call::MethodCall(call)) => {
-                debug!(
-                    method = %call.method,
-                    id = ?call.id,
-                    params = %serde_json::to_string_pretty(&call.params).unwrap_or_default(),
-                    "Received JSON-RPC request"
+    ) -> Result<Option<JsonRpcResponse>, McpError> {
+        self.handle_session_request(&Arc::new(Session::detached()), request)
+            .await
+    }
+
+    /// Handle a request on behalf of `session`. Notifications produce no
+    /// response, so `Ok(None)` means there is nothing to send back.
+    pub async fn handle_session_request(
+        &self,
+        session: &Arc<Session>,
+        request: JsonRpcRequest,
+    ) -> Result<Option<JsonRpcResponse>, McpError> {
+        match request {
+            JsonRpcRequest::Single(call) => self.handle_single(session, call).await,
+            JsonRpcRequest::Batch(calls) => Ok(self.handle_batch(session, calls).await),
+        }
+    }
+
+    async fn handle_single(
+        &self,
+        session: &Arc<Session>,
+        call: Call,
+    ) -> Result<Option<JsonRpcResponse>, McpError> {
+        match call {
+            Call::MethodCall(call) => {
+                let id = call.id.clone();
+                let cancellation = session.begin_request(&id);
+                // Dropping the handler stops the request at its next await point;
+                // the token lets tools wind down work that runs outside of it.
+                let span = info_span!(
+                    logging::REQUEST_SPAN,
+                    session_id = %session.id(),
+                    request_id = %serde_json::to_string(&id).unwrap_or_default(),
+                    method = %call.method
                 );
-                (call.id, call.method, call.params)
+                let handler = self
+                    .handle_call(session, call, cancellation.clone())
+                    .instrument(span);
+                let result = tokio::select! {
+                    result = handler => Some(result),
+                    _ = cancellation.cancelled() => None,
+                };
+                session.end_request(&id);
+                match result {
+                    Some(result) => result.map(Some),
+                    None => {
+                        info!(session_id = %session.id(), id = ?id, "Request cancelled");
+                        Ok(None)
+                    }
+                }
             }
-            JsonRpcRequest::Single(Call::Notification(notification)) => {
-                debug!(
-                    method = %notification.method,
-                    params = %serde_json::to_string_pretty(&notification.params).unwrap_or_default(),
-                    "Received JSON-RPC notification"
-                );
-                // For now, just return an empty success response
-                // TODO
-                return Ok(JsonRpcResponse::Single(Output::Success(Success {
-                    jsonrpc: Some(Version::V2),
-                    result: serde_json::json!({}),
-                    id: Id::Num(0),
-                })));
+            Call::Notification(notification) => {
+                self.handle_notification(session, notification).await;
+                Ok(None)
             }
-            _ => {
-                return Ok(JsonRpcResponse::Single(Output::Failure(Failure {
+            Call::Invalid { id } => {
+                warn!(id = ?id, "Received invalid JSON-RPC call");
+                Ok(Some(JsonRpcResponse::Single(Output::Failure(Failure {
                     jsonrpc: Some(Version::V2),
                     error: McpError::InvalidRequest.into(),
-                    id: Id::Num(0),
-                })));
+                    id,
+                }))))
             }
-        };
+        }
+    }
+
+    /// Process every call in a batch concurrently. The response holds one
+    /// output per method call; a batch of only notifications gets no response.
+    async fn handle_batch(
+        &self,
+        session: &Arc<Session>,
+        calls: Vec<Call>,
+    ) -> Option<JsonRpcResponse> {
+        if calls.is_empty() {
+            warn!("Received empty JSON-RPC batch");
+            return Some(JsonRpcResponse::Single(Output::Failure(Failure {
+                jsonrpc: Some(Version::V2),
+                error: McpError::InvalidRequest.into(),
+                id: Id::Null,
+            })));
+        }
+
+        debug!(num_calls = %calls.len(), "Received JSON-RPC batch");
+
+        let outputs: Vec<Output> = join_all(calls.into_iter().map(|call| async move {
+            let id = match &call {
+                Call::MethodCall(call) => Some(call.id.clone()),
+                Call::Invalid { id } => Some(id.clone()),
+                Call::Notification(_) => None,
+            };
+            match self.handle_single(session, call).await {
+                Ok(Some(JsonRpcResponse::Single(output))) => Some(output),
+                Ok(_) => None,
+                Err(e) => id.map(|id| {
+                    Output::Failure(Failure {
+                        jsonrpc: Some(Version::V2),
+                        error: e.into(),
+                        id,
+                    })
+                }),
+            }
+        }))
+        .await
+        .into_iter()
+        .flatten()
+        .collect();
+
+        debug!(num_outputs = %outputs.len(), "Sending JSON-RPC batch response");
+
+        (!outputs.is_empty()).then_some(JsonRpcResponse::Batch(outputs))
+    }
+
+    async fn handle_call(
+        &self,
+        session: &Arc<Session>,
+        call: MethodCall,
+        cancellation: CancellationToken,
+    ) -> Result<JsonRpcResponse, McpError> {
+        debug!(
+            method = %call.method,
+            id = ?call.id,
+            params = %serde_json::to_string_pretty(&call.params).unwrap_or_default(),
+            "Received JSON-RPC request"
+        );
+        let MethodCall {
+            id, method, params, ..
+        } = call;
 
         let response = match method.as_str() {
             "initialize" => {
                 info!("Processing initialize request");
-                let tools_lock = self.tools.lock().await;
+                let mut protocol_version = LATEST_PROTOCOL_VERSION;
+                if params != Params::None {
+                    let request: InitializeParams = params.parse().map_err(|_| {
+                        warn!("Failed to parse initialize request parameters");
+                        McpError::InvalidParams
+                    })?;
+                    info!(
+                        session_id = %session.id(),
+                        client_name = %request.client_info.name,
+                        client_version = %request.client_info.version,
+                        protocol_version = %request.protocol_version,
+                        "Client connected"
+                    );
+                    protocol_version = negotiate_protocol_version(&request.protocol_version);
+                    if protocol_version != request.protocol_version {
+                        warn!(
+                            session_id = %session.id(),
+                            requested = %request.protocol_version,
+                            offered = %protocol_version,
+                            "Client requested an unsupported protocol version"
+                        );
+                    }
+                    session.set_client(request.client_info, request.capabilities);
+                }
+                session.set_protocol_version(protocol_version);
+                let tools: HashMap<String, bool> = self
+                    .tools
+                    .read()
+                    .unwrap()
+                    .keys()
+                    .map(|k| (k.clone(), true))
+                    .collect();
+                let num_tools = tools.len();
+                let has_resources = !self.resources.read().unwrap().is_empty()
+                    || !self.resource_templates.read().unwrap().is_empty();
                 let capabilities = ServerCapabilities {
-                    tools: tools_lock.keys().map(|k| (k.clone(), true)).collect(),
+                    tools,
+                    resources: has_resources.then_some(ResourcesCapability {
+                        subscribe: true,
+                        list_changed: false,
+                    }),
+                    prompts: (!self.prompts.read().unwrap().is_empty())
+                        .then(PromptsCapability::default),
+                    logging: Some(LoggingCapability::default()),
                 };
 
                 let result = InitializeResult {
-                    protocol_version: LATEST_PROTOCOL_VERSION.to_string(),
+                    protocol_version: protocol_version.to_string(),
                     capabilities,
                     server_info: ServerInfo {
                         name: self.name.clone(),
@@ -116,8 +404,8 @@ impl McpServer {
                 debug!(
                     server_name = %self.name,
                     server_version = %self.version,
-                    protocol_version = %LATEST_PROTOCOL_VERSION,
-                    num_tools = %tools_lock.len(),
+                    protocol_version = %protocol_version,
+                    num_tools = %num_tools,
                     "Sending initialize response"
                 );
 
@@ -129,13 +417,22 @@ impl McpServer {
             }
             "tools/list" => {
                 info!("Processing tools/list request");
-                let tools_lock = self.tools.lock().await;
-                let tools: Vec<Tool> = tools_lock
+                let tools: Vec<Tool> = self
+                    .tools
+                    .read()
+                    .unwrap()
                     .values()
                     .map(|tool| Tool {
                         name: tool.name().to_string(),
                         description: tool.description().to_string(),
                         input_schema: tool.input_schema(),
+                        output_schema: tool.output_schema(),
+                        title: tool.title().map(str::to_string),
+                        annotations: tool.annotations(),
+                    })
+                    .map(|mut tool| {
+                        version::downgrade_tool(&mut tool, session.protocol_version());
+                        tool
                     })
                     .collect();
 
@@ -178,11 +475,16 @@ impl McpServer {
                     "Executing tool"
                 );
 
-                let tools_lock = self.tools.lock().await;
-                let tool = tools_lock.get(&request.name).ok_or_else(|| {
-                    warn!(tool = %request.name, "Tool not found");
-                    McpError::MethodNotFound
-                })?;
+                let tool = self
+                    .tools
+                    .read()
+                    .unwrap()
+                    .get(&request.name)
+                    .cloned()
+                    .ok_or_else(|| {
+                        warn!(tool = %request.name, "Tool not found");
+                        McpError::MethodNotFound
+                    })?;
 
                 let args = match request.arguments {
                     Some(args) => serde_json::Value::Object(args.into_iter().collect()),
@@ -195,18 +497,15 @@ impl McpServer {
                     "Executing tool with arguments"
                 );
 
-                match tool.execute(args).await {
-                    Ok(result) => {
-                        let content = result
-                            .content
-                            .into_iter()
-                            .map(|c| Content::Text { text: c.text })
-                            .collect();
-
-                        let result = CallToolResult {
-                            content,
-                            is_error: Some(result.is_error),
-                        };
+                let ctx = ToolContext::new(cancellation)
+                    .with_peer(Arc::new(RequestPeer::new(session.clone(), id.clone())))
+                    .with_progress_token(request.meta.and_then(|meta| meta.progress_token));
+
+                match tool.execute(args, ctx).await {
+                    Ok(mut result) => {
+                        // Always tell the client whether the call failed
+                        result.is_error = Some(result.is_error.unwrap_or(false));
+                        version::downgrade_tool_result(&mut result, session.protocol_version());
 
                         debug!(
                             tool = %request.name,
@@ -235,6 +534,287 @@ impl McpServer {
                     }
                 }
             }
+            "resources/list" => {
+                info!("Processing resources/list request");
+                let resources: Vec<Resource> = self
+                    .resources
+                    .read()
+                    .unwrap()
+                    .values()
+                    .map(|resource| Resource {
+                        uri: resource.uri().to_string(),
+                        name: resource.name().to_string(),
+                        description: resource.description().map(str::to_string),
+                        mime_type: resource.mime_type().map(str::to_string),
+                    })
+                    .collect();
+
+                let result = ListResourcesResult {
+                    resources,
+                    next_cursor: None,
+                };
+
+                debug!(
+                    num_resources = %result.resources.len(),
+                    resource_uris = ?result.resources.iter().map(|r| &r.uri).collect::<Vec<_>>(),
+                    "Sending resources list response"
+                );
+
+                JsonRpcResponse::Single(Output::Success(Success {
+                    jsonrpc: Some(Version::V2),
+                    result: serde_json::to_value(result)?,
+                    id,
+                }))
+            }
+            "resources/templates/list" => {
+                info!("Processing resources/templates/list request");
+                let resource_templates: Vec<ResourceTemplate> = self
+                    .resource_templates
+                    .read()
+                    .unwrap()
+                    .iter()
+                    .map(|template| ResourceTemplate {
+                        uri_template: template.uri_template().to_string(),
+                        name: template.name().to_string(),
+                        description: template.description().map(str::to_string),
+                        mime_type: template.mime_type().map(str::to_string),
+                    })
+                    .collect();
+
+                let result = ListResourceTemplatesResult {
+                    resource_templates,
+                    next_cursor: None,
+                };
+
+                debug!(
+                    num_templates = %result.resource_templates.len(),
+                    "Sending resource templates list response"
+                );
+
+                JsonRpcResponse::Single(Output::Success(Success {
+                    jsonrpc: Some(Version::V2),
+                    result: serde_json::to_value(result)?,
+                    id,
+                }))
+            }
+            "resources/read" => {
+                info!("Processing resources/read request");
+                let request: ReadResourceRequest = params.parse().map_err(|_| {
+                    warn!("Failed to parse resources/read request parameters");
+                    McpError::InvalidParams
+                })?;
+
+                debug!(uri = %request.uri, "Reading resource");
+
+                let resource = self.resources.read().unwrap().get(&request.uri).cloned();
+                let contents = match resource {
+                    Some(resource) => Some(resource.read().await),
+                    None => {
+                        let template = self
+                            .resource_templates
+                            .read()
+                            .unwrap()
+                            .iter()
+                            .find(|t| t.matches(&request.uri))
+                            .cloned();
+                        match template {
+                            Some(template) => Some(template.read(&request.uri).await),
+                            None => None,
+                        }
+                    }
+                };
+
+                match contents {
+                    Some(Ok(contents)) => {
+                        debug!(
+                            uri = %request.uri,
+                            num_contents = %contents.len(),
+                            "Resource read successful"
+                        );
+                        JsonRpcResponse::Single(Output::Success(Success {
+                            jsonrpc: Some(Version::V2),
+                            result: serde_json::to_value(ReadResourceResult { contents })?,
+                            id,
+                        }))
+                    }
+                    Some(Err(e)) => {
+                        warn!(uri = %request.uri, error = %e, "Resource read failed");
+                        JsonRpcResponse::Single(Output::Failure(Failure {
+                            jsonrpc: Some(Version::V2),
+                            error: JsonRpcError {
+                                code: ErrorCode::InternalError,
+                                message: e,
+                                data: None,
+                            },
+                            id,
+                        }))
+                    }
+                    None => {
+                        warn!(uri = %request.uri, "Resource not found");
+                        JsonRpcResponse::Single(Output::Failure(Failure {
+                            jsonrpc: Some(Version::V2),
+                            error: McpError::ResourceNotFound.into(),
+                            id,
+                        }))
+                    }
+                }
+            }
+            "resources/subscribe" | "resources/unsubscribe" => {
+                info!(method = %method, "Processing resource subscription request");
+                let request: SubscribeRequest = params.parse().map_err(|_| {
+                    warn!("Failed to parse subscription request parameters");
+                    McpError::InvalidParams
+                })?;
+
+                if method == "resources/subscribe" {
+                    session.subscribe(&request.uri);
+                } else {
+                    session.unsubscribe(&request.uri);
+                }
+
+                debug!(
+                    session_id = %session.id(),
+                    uri = %request.uri,
+                    subscribed = %session.is_subscribed(&request.uri),
+                    "Updated resource subscription"
+                );
+
+                JsonRpcResponse::Single(Output::Success(Success {
+                    jsonrpc: Some(Version::V2),
+                    result: serde_json::json!({}),
+                    id,
+                }))
+            }
+            "logging/setLevel" => {
+                info!("Processing logging/setLevel request");
+                let request: SetLevelRequest = params.parse().map_err(|_| {
+                    warn!("Failed to parse logging/setLevel request parameters");
+                    McpError::InvalidParams
+                })?;
+
+                session.set_log_level(request.level);
+                debug!(
+                    session_id = %session.id(),
+                    level = ?request.level,
+                    "Updated session log level"
+                );
+
+                JsonRpcResponse::Single(Output::Success(Success {
+                    jsonrpc: Some(Version::V2),
+                    result: serde_json::json!({}),
+                    id,
+                }))
+            }
+            "prompts/list" => {
+                info!("Processing prompts/list request");
+                let prompts: Vec<Prompt> = self
+                    .prompts
+                    .read()
+                    .unwrap()
+                    .values()
+                    .map(|prompt| Prompt {
+                        name: prompt.name().to_string(),
+                        description: Some(prompt.description().to_string())
+                            .filter(|d| !d.is_empty()),
+                        arguments: prompt.arguments(),
+                    })
+                    .collect();
+
+                let result = ListPromptsResult {
+                    prompts,
+                    next_cursor: None,
+                };
+
+                debug!(
+                    num_prompts = %result.prompts.len(),
+                    prompt_names = ?result.prompts.iter().map(|p| &p.name).collect::<Vec<_>>(),
+                    "Sending prompts list response"
+                );
+
+                JsonRpcResponse::Single(Output::Success(Success {
+                    jsonrpc: Some(Version::V2),
+                    result: serde_json::to_value(result)?,
+                    id,
+                }))
+            }
+            "prompts/get" => {
+                info!("Processing prompts/get request");
+                let request: GetPromptRequest = params.parse().map_err(|_| {
+                    warn!("Failed to parse prompts/get request parameters");
+                    McpError::InvalidParams
+                })?;
+
+                let prompt = self.prompts.read().unwrap().get(&request.name).cloned();
+                let Some(prompt) = prompt else {
+                    warn!(prompt = %request.name, "Prompt not found");
+                    return Ok(JsonRpcResponse::Single(Output::Failure(Failure {
+                        jsonrpc: Some(Version::V2),
+                        error: JsonRpcError::invalid_params(format!(
+                            "Unknown prompt: {}",
+                            request.name
+                        )),
+                        id,
+                    })));
+                };
+
+                let args = request.arguments.unwrap_or_default();
+                if let Some(missing) = prompt
+                    .arguments()
+                    .into_iter()
+                    .find(|arg| arg.required == Some(true) && !args.contains_key(&arg.name))
+                {
+                    warn!(
+                        prompt = %request.name,
+                        argument = %missing.name,
+                        "Missing required prompt argument"
+                    );
+                    return Ok(JsonRpcResponse::Single(Output::Failure(Failure {
+                        jsonrpc: Some(Version::V2),
+                        error: JsonRpcError::invalid_params(format!(
+                            "Missing required argument: {}",
+                            missing.name
+                        )),
+                        id,
+                    })));
+                }
+
+                debug!(prompt = %request.name, args = ?args, "Rendering prompt");
+
+                match prompt.render(args).await {
+                    Ok(messages) => {
+                        let mut result = GetPromptResult {
+                            description: Some(prompt.description().to_string())
+                                .filter(|d| !d.is_empty()),
+                            messages,
+                        };
+                        version::downgrade_prompt_result(&mut result, session.protocol_version());
+
+                        debug!(
+                            prompt = %request.name,
+                            num_messages = %result.messages.len(),
+                            "Prompt rendered successfully"
+                        );
+
+                        JsonRpcResponse::Single(Output::Success(Success {
+                            jsonrpc: Some(Version::V2),
+                            result: serde_json::to_value(result)?,
+                            id,
+                        }))
+                    }
+                    Err(e) => {
+                        warn!(prompt = %request.name, error = %e, "Prompt rendering failed");
+                        JsonRpcResponse::Single(Output::Failure(Failure {
+                            jsonrpc: Some(Version::V2),
+                            error: JsonRpcError {
+                                code: ErrorCode::InternalError,
+                                message: e,
+                                data: None,
+                            },
+                            id,
+                        }))
+                    }
+                }
+            }
             _ => {
                 warn!(method = %method, "Unknown method called");
                 JsonRpcResponse::Single(Output::Failure(Failure {
@@ -255,17 +835,75 @@ impl McpServer {
         Ok(response)
     }
 
-    pub fn handle_notification(
-        &mut self,
-        method: &str,
-        _params: Option<serde_json::Value>,
-    ) -> Result<(), McpError> {
-        match method {
+    async fn handle_notification(&self, session: &Arc<Session>, notification: Notification) {
+        debug!(
+            session_id = %session.id(),
+            method = %notification.method,
+            params = %serde_json::to_string_pretty(&notification.params).unwrap_or_default(),
+            "Received JSON-RPC notification"
+        );
+        match notification.method.as_str() {
             "notifications/initialized" => {
-                info!("Client completed initialization");
-                Ok(())
+                info!(session_id = %session.id(), "Client completed initialization");
+                Self::refresh_roots(session);
+            }
+            "notifications/cancelled" => match notification.params.parse::<CancelledNotification>()
+            {
+                Ok(cancelled) => {
+                    let found = session.cancel(&cancelled.request_id);
+                    info!(
+                        session_id = %session.id(),
+                        request_id = ?cancelled.request_id,
+                        reason = ?cancelled.reason,
+                        found = %found,
+                        "Client cancelled a request"
+                    );
+                }
+                Err(_) => warn!("Failed to parse notifications/cancelled parameters"),
+            },
+            "notifications/roots/list_changed" => {
+                info!(session_id = %session.id(), "Client roots changed");
+                Self::refresh_roots(session);
+            }
+            method => {
+                debug!(method = %method, "Ignoring unknown notification");
             }
-            _ => Err(McpError::MethodNotFound),
         }
     }
+
+    /// Fetch the client's roots in the background and cache them on the
+    /// session. The response arrives through the same transport that is
+    /// delivering this notification, so it must not be awaited inline.
+    fn refresh_roots(session: &Arc<Session>) {
+        if session
+            .client_capabilities()
+            .and_then(|capabilities| capabilities.roots)
+            .is_none()
+        {
+            return;
+        }
+        let session = session.clone();
+        tokio::spawn(async move {
+            let roots = match session.request("roots/list", serde_json::Value::Null).await {
+                Ok(result) => serde_json::from_value::<ListRootsResult>(result),
+                Err(e) => {
+                    warn!(session_id = %session.id(), error = %e.message, "roots/list failed");
+                    return;
+                }
+            };
+            match roots {
+                Ok(result) => {
+                    info!(
+                        session_id = %session.id(),
+                        count = %result.roots.len(),
+                        "Updated client roots"
+                    );
+                    session.set_roots(result.roots);
+                }
+                Err(e) => {
+                    warn!(session_id = %session.id(), error = %e, "Invalid roots/list result")
+                }
+            }
+        });
+    }
 }
@@ -0,0 +1,511 @@
+use crate::McpError;
+use async_trait::async_trait;
+use jsonrpc_core::{
+    Call, Error as JsonRpcError, Id, MethodCall, Notification, Output, Params,
+    Response as JsonRpcResponse, Version,
+};
+use offeryn_types::{
+    CancellationToken, ClientCapabilities, Implementation, LoggingLevel,
+    LoggingMessageNotification, Peer, Root, LATEST_PROTOCOL_VERSION,
+};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// A client connection as seen by the server.
+///
+/// Transports create one session per connection via [`McpServer::connect`](crate::McpServer::connect)
+/// and drain the paired receiver to deliver server-initiated messages to the client.
+pub struct Session {
+    id: String,
+    sender: Option<mpsc::Sender<Call>>,
+    /// Streams a transport opened for individual requests
+    routes: Mutex<HashMap<Id, mpsc::Sender<Call>>>,
+    subscriptions: Mutex<HashSet<String>>,
+    in_flight: Mutex<HashMap<Id, CancellationToken>>,
+    client_info: Mutex<Option<Implementation>>,
+    client_capabilities: Mutex<Option<ClientCapabilities>>,
+    roots: Mutex<Option<Vec<Root>>>,
+    protocol_version: Mutex<&'static str>,
+    log_level: Mutex<LoggingLevel>,
+    next_request_id: AtomicU64,
+    pending: Mutex<HashMap<Id, oneshot::Sender<Output>>>,
+    /// Set once the client can no longer answer requests
+    requests_closed: AtomicBool,
+    closed: AtomicBool,
+}
+
+impl Session {
+    pub(crate) fn new(sender: mpsc::Sender<Call>) -> Self {
+        Self::with_sender(Some(sender))
+    }
+
+    /// A session with no way to reach the client, used for one-off requests.
+    pub(crate) fn detached() -> Self {
+        Self::with_sender(None)
+    }
+
+    fn with_sender(sender: Option<mpsc::Sender<Call>>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            sender,
+            routes: Mutex::new(HashMap::new()),
+            subscriptions: Mutex::new(HashSet::new()),
+            in_flight: Mutex::new(HashMap::new()),
+            client_info: Mutex::new(None),
+            client_capabilities: Mutex::new(None),
+            roots: Mutex::new(None),
+            // Clients that skip `initialize` get the full feature set
+            protocol_version: Mutex::new(LATEST_PROTOCOL_VERSION),
+            // Until the client picks a level, skip debug chatter
+            log_level: Mutex::new(LoggingLevel::Info),
+            next_request_id: AtomicU64::new(1),
+            pending: Mutex::new(HashMap::new()),
+            requests_closed: AtomicBool::new(false),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Send messages tied to request `id` through `sender` instead of the
+    /// session's channel, until [`unroute_request`](Self::unroute_request).
+    pub(crate) fn route_request(&self, id: Id, sender: mpsc::Sender<Call>) {
+        self.routes.lock().unwrap().insert(id, sender);
+    }
+
+    pub(crate) fn unroute_request(&self, id: &Id) {
+        self.routes.lock().unwrap().remove(id);
+    }
+
+    /// Where a message sent on behalf of request `scope` goes: the request's
+    /// own stream while it is open, otherwise the session's channel.
+    fn sender_for(&self, scope: Option<&Id>) -> Option<mpsc::Sender<Call>> {
+        scope
+            .and_then(|id| self.routes.lock().unwrap().get(id).cloned())
+            .filter(|sender| !sender.is_closed())
+            .or_else(|| self.sender.clone())
+    }
+
+    /// Send a JSON-RPC notification to the client.
+    pub async fn notify(&self, method: &str, params: Value) -> Result<(), McpError> {
+        self.notify_in(None, method, params).await
+    }
+
+    /// [`notify`](Self::notify) on behalf of the request `scope`.
+    pub(crate) async fn notify_in(
+        &self,
+        scope: Option<&Id>,
+        method: &str,
+        params: Value,
+    ) -> Result<(), McpError> {
+        let Some(sender) = self.sender_for(scope) else {
+            debug!(
+                session_id = %self.id,
+                method = %method,
+                "Dropping notification for detached session"
+            );
+            return Ok(());
+        };
+
+        debug!(session_id = %self.id, method = %method, "Sending notification");
+        sender
+            .send(notification(method, params)?)
+            .await
+            .map_err(|_| {
+                warn!(session_id = %self.id, "Session channel closed");
+                McpError::InternalError
+            })
+    }
+
+    /// Like [`notify`](Self::notify), but never waits: the notification is
+    /// dropped if the session's queue is full.
+    pub fn try_notify(&self, method: &str, params: Value) -> Result<(), McpError> {
+        self.try_notify_in(None, method, params)
+    }
+
+    fn try_notify_in(
+        &self,
+        scope: Option<&Id>,
+        method: &str,
+        params: Value,
+    ) -> Result<(), McpError> {
+        let Some(sender) = self.sender_for(scope) else {
+            return Ok(());
+        };
+        sender
+            .try_send(notification(method, params)?)
+            .map_err(|_| McpError::InternalError)
+    }
+
+    /// Send a request to the client and wait for its response. Transports hand
+    /// the response back through [`handle_response`](Self::handle_response).
+    pub async fn request(&self, method: &str, params: Value) -> Result<Value, JsonRpcError> {
+        self.request_in(None, method, params).await
+    }
+
+    /// [`request`](Self::request) on behalf of the request `scope`.
+    pub(crate) async fn request_in(
+        &self,
+        scope: Option<&Id>,
+        method: &str,
+        params: Value,
+    ) -> Result<Value, JsonRpcError> {
+        let Some(sender) = self.sender_for(scope) else {
+            return Err(closed_error("Session has no connected client"));
+        };
+        let params = match params {
+            Value::Object(map) => Params::Map(map),
+            Value::Null => Params::None,
+            _ => return Err(JsonRpcError::invalid_params("Params must be an object")),
+        };
+
+        let id = Id::Num(self.next_request_id.fetch_add(1, Ordering::SeqCst));
+        let (tx, rx) = oneshot::channel();
+        {
+            // Checked under the lock so a concurrent close can't miss this entry
+            let mut pending = self.pending.lock().unwrap();
+            if self.requests_closed.load(Ordering::SeqCst) {
+                return Err(closed_error("Session closed"));
+            }
+            pending.insert(id.clone(), tx);
+        }
+        // Forget the request if the caller stops waiting for it
+        let _pending = PendingGuard {
+            session: self,
+            id: id.clone(),
+        };
+
+        debug!(session_id = %self.id, method = %method, id = ?id, "Sending request to client");
+        sender
+            .send(Call::MethodCall(MethodCall {
+                jsonrpc: Some(Version::V2),
+                method: method.to_string(),
+                params,
+                id,
+            }))
+            .await
+            .map_err(|_| closed_error("Session channel closed"))?;
+
+        match rx.await {
+            Ok(Output::Success(success)) => Ok(success.result),
+            Ok(Output::Failure(failure)) => Err(failure.error),
+            Err(_) => Err(closed_error("Session closed before the client responded")),
+        }
+    }
+
+    /// Deliver the client's response to a request sent with [`request`](Self::request).
+    pub fn handle_response(&self, response: JsonRpcResponse) {
+        let outputs = match response {
+            JsonRpcResponse::Single(output) => vec![output],
+            JsonRpcResponse::Batch(outputs) => outputs,
+        };
+        for output in outputs {
+            let sender = self.pending.lock().unwrap().remove(output.id());
+            match sender {
+                Some(sender) => {
+                    let _ = sender.send(output);
+                }
+                None => warn!(
+                    session_id = %self.id,
+                    id = ?output.id(),
+                    "Dropping response to unknown request"
+                ),
+            }
+        }
+    }
+
+    pub(crate) fn set_log_level(&self, level: LoggingLevel) {
+        *self.log_level.lock().unwrap() = level;
+    }
+
+    /// The minimum level of log messages the client wants to receive.
+    pub fn log_level(&self) -> LoggingLevel {
+        *self.log_level.lock().unwrap()
+    }
+
+    /// Send `notifications/message` to the client if `level` passes its filter.
+    pub async fn log(
+        &self,
+        level: LoggingLevel,
+        logger: Option<&str>,
+        data: Value,
+    ) -> Result<(), McpError> {
+        self.log_in(None, level, logger, data).await
+    }
+
+    pub(crate) async fn log_in(
+        &self,
+        scope: Option<&Id>,
+        level: LoggingLevel,
+        logger: Option<&str>,
+        data: Value,
+    ) -> Result<(), McpError> {
+        if level < self.log_level() {
+            return Ok(());
+        }
+        let params = serde_json::to_value(LoggingMessageNotification {
+            level,
+            logger: logger.map(str::to_string),
+            data,
+        })?;
+        self.notify_in(scope, "notifications/message", params).await
+    }
+
+    pub(crate) fn try_log(
+        &self,
+        scope: Option<&Id>,
+        level: LoggingLevel,
+        logger: Option<&str>,
+        data: Value,
+    ) -> Result<(), McpError> {
+        if level < self.log_level() {
+            return Ok(());
+        }
+        let params = serde_json::to_value(LoggingMessageNotification {
+            level,
+            logger: logger.map(str::to_string),
+            data,
+        })?;
+        self.try_notify_in(scope, "notifications/message", params)
+    }
+
+    /// Record what the client told us about itself in `initialize`.
+    pub(crate) fn set_client(&self, info: Implementation, capabilities: ClientCapabilities) {
+        *self.client_info.lock().unwrap() = Some(info);
+        *self.client_capabilities.lock().unwrap() = Some(capabilities);
+    }
+
+    pub fn client_info(&self) -> Option<Implementation> {
+        self.client_info.lock().unwrap().clone()
+    }
+
+    pub fn client_capabilities(&self) -> Option<ClientCapabilities> {
+        self.client_capabilities.lock().unwrap().clone()
+    }
+
+    pub(crate) fn set_protocol_version(&self, version: &'static str) {
+        *self.protocol_version.lock().unwrap() = version;
+    }
+
+    /// The protocol revision agreed in `initialize`.
+    pub fn protocol_version(&self) -> &'static str {
+        *self.protocol_version.lock().unwrap()
+    }
+
+    pub(crate) fn set_roots(&self, roots: Vec<Root>) {
+        *self.roots.lock().unwrap() = Some(roots);
+    }
+
+    /// The client's roots from its last `roots/list` response.
+    pub fn roots(&self) -> Option<Vec<Root>> {
+        self.roots.lock().unwrap().clone()
+    }
+
+    pub(crate) fn subscribe(&self, uri: &str) {
+        self.subscriptions.lock().unwrap().insert(uri.to_string());
+    }
+
+    pub(crate) fn unsubscribe(&self, uri: &str) {
+        self.subscriptions.lock().unwrap().remove(uri);
+    }
+
+    pub fn is_subscribed(&self, uri: &str) -> bool {
+        self.subscriptions.lock().unwrap().contains(uri)
+    }
+
+    /// Track a running request so the client can cancel it by id. A request
+    /// a transport already registered while it was queued keeps its token.
+    pub(crate) fn begin_request(&self, id: &Id) -> CancellationToken {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if self.closed.load(Ordering::SeqCst) {
+            // Requests that start after the client went away never run
+            let token = CancellationToken::new();
+            token.cancel();
+            return token;
+        }
+        in_flight.entry(id.clone()).or_default().clone()
+    }
+
+    pub(crate) fn end_request(&self, id: &Id) {
+        self.in_flight.lock().unwrap().remove(id);
+    }
+
+    /// Cancel the in-flight request with `id`. Returns false if it already
+    /// finished or was never seen.
+    pub(crate) fn cancel(&self, id: &Id) -> bool {
+        match self.in_flight.lock().unwrap().remove(id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Fail requests awaiting the client and refuse new ones, for when the
+    /// client has stopped reading. Running calls are left to finish.
+    pub(crate) fn close_requests(&self) {
+        let mut pending = self.pending.lock().unwrap();
+        self.requests_closed.store(true, Ordering::SeqCst);
+        pending.clear();
+    }
+
+    /// Cancel everything still running and fail requests awaiting the client.
+    pub(crate) fn close(&self) {
+        {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            self.closed.store(true, Ordering::SeqCst);
+            for (_, token) in in_flight.drain() {
+                token.cancel();
+            }
+        }
+        self.close_requests();
+    }
+}
+
+struct PendingGuard<'a> {
+    session: &'a Session,
+    id: Id,
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        self.session.pending.lock().unwrap().remove(&self.id);
+    }
+}
+
+fn closed_error(message: &str) -> JsonRpcError {
+    JsonRpcError {
+        code: jsonrpc_core::ErrorCode::InternalError,
+        message: message.to_string(),
+        data: None,
+    }
+}
+
+fn notification(method: &str, params: Value) -> Result<Call, McpError> {
+    let params = match params {
+        Value::Object(map) => Params::Map(map),
+        Value::Null => Params::None,
+        _ => return Err(McpError::InvalidParams),
+    };
+    Ok(Call::Notification(Notification {
+        jsonrpc: Some(Version::V2),
+        method: method.to_string(),
+        params,
+    }))
+}
+
+/// The session as seen by one request. Messages the request sends reach the
+/// client on the request's own stream when the transport opened one.
+pub(crate) struct RequestPeer {
+    session: Arc<Session>,
+    id: Id,
+}
+
+impl RequestPeer {
+    pub(crate) fn new(session: Arc<Session>, id: Id) -> Self {
+        Self { session, id }
+    }
+}
+
+#[async_trait]
+impl Peer for RequestPeer {
+    fn session_id(&self) -> &str {
+        self.session.id()
+    }
+
+    fn client_info(&self) -> Option<Implementation> {
+        self.session.client_info()
+    }
+
+    fn client_capabilities(&self) -> Option<ClientCapabilities> {
+        self.session.client_capabilities()
+    }
+
+    fn roots(&self) -> Option<Vec<Root>> {
+        self.session.roots()
+    }
+
+    fn protocol_version(&self) -> &'static str {
+        self.session.protocol_version()
+    }
+
+    async fn notify(&self, method: &str, params: Value) -> Result<(), String> {
+        self.session
+            .notify_in(Some(&self.id), method, params)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn request(&self, method: &str, params: Value) -> Result<Value, String> {
+        self.session
+            .request_in(Some(&self.id), method, params)
+            .await
+            .map_err(|e| e.message)
+    }
+
+    async fn log(
+        &self,
+        level: LoggingLevel,
+        logger: Option<&str>,
+        data: Value,
+    ) -> Result<(), String> {
+        self.session
+            .log_in(Some(&self.id), level, logger, data)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[async_trait]
+impl Peer for Session {
+    fn session_id(&self) -> &str {
+        self.id()
+    }
+
+    fn client_info(&self) -> Option<Implementation> {
+        Session::client_info(self)
+    }
+
+    fn client_capabilities(&self) -> Option<ClientCapabilities> {
+        Session::client_capabilities(self)
+    }
+
+    fn roots(&self) -> Option<Vec<Root>> {
+        Session::roots(self)
+    }
+
+    fn protocol_version(&self) -> &'static str {
+        Session::protocol_version(self)
+    }
+
+    async fn notify(&self, method: &str, params: Value) -> Result<(), String> {
+        Session::notify(self, method, params)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn request(&self, method: &str, params: Value) -> Result<Value, String> {
+        Session::request(self, method, params)
+            .await
+            .map_err(|e| e.message)
+    }
+
+    async fn log(
+        &self,
+        level: LoggingLevel,
+        logger: Option<&str>,
+        data: Value,
+    ) -> Result<(), String> {
+        Session::log(self, level, logger, data)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
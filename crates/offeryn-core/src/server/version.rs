@@ -0,0 +1,77 @@
+use offeryn_types::{CallToolResult, Content, GetPromptResult, Tool};
+use tracing::debug;
+
+// Protocol versions are dates, so revisions compare as plain strings.
+
+/// Audio content and tool annotations.
+const V2025_03_26: &str = "2025-03-26";
+/// Structured tool output, tool titles and resource links.
+const V2025_06_18: &str = "2025-06-18";
+
+/// Remove tool fields the client's revision doesn't define.
+pub(crate) fn downgrade_tool(tool: &mut Tool, version: &str) {
+    if version < V2025_06_18 {
+        tool.output_schema = None;
+        // Older revisions only carry the title inside the annotations
+        if let Some(title) = tool.title.take() {
+            let annotations = tool.annotations.get_or_insert_with(Default::default);
+            annotations.title.get_or_insert(title);
+        }
+    }
+    if version < V2025_03_26 {
+        tool.annotations = None;
+    }
+}
+
+/// Rewrite a tool result into content the client's revision understands.
+pub(crate) fn downgrade_tool_result(result: &mut CallToolResult, version: &str) {
+    if version < V2025_06_18 {
+        // Results built by hand may have no text fallback for the structured
+        // value, so add one rather than lose it
+        if let Some(structured) = result.structured_content.take() {
+            if !result
+                .content
+                .iter()
+                .any(|content| matches!(content, Content::Text { .. }))
+            {
+                let text = serde_json::to_string(&structured).unwrap_or_default();
+                result.content.push(Content::text(text));
+            }
+        }
+    }
+    let before = result.content.len();
+    result
+        .content
+        .retain_mut(|content| downgrade_content(content, version));
+    if result.content.len() != before {
+        debug!(
+            protocol_version = %version,
+            "Dropped audio content unsupported by the client"
+        );
+    }
+}
+
+/// Rewrite prompt messages into content the client's revision understands.
+pub(crate) fn downgrade_prompt_result(result: &mut GetPromptResult, version: &str) {
+    let before = result.messages.len();
+    result
+        .messages
+        .retain_mut(|message| downgrade_content(&mut message.content, version));
+    if result.messages.len() != before {
+        debug!(
+            protocol_version = %version,
+            "Dropped audio prompt messages unsupported by the client"
+        );
+    }
+}
+
+/// Rewrite a content block for the client's revision, returning false if it
+/// has no equivalent there.
+fn downgrade_content(content: &mut Content, version: &str) -> bool {
+    if version < V2025_06_18 {
+        if let Content::ResourceLink { uri, name, .. } = content {
+            *content = Content::text(format!("{} ({})", name, uri));
+        }
+    }
+    !(version < V2025_03_26 && matches!(content, Content::Audio { .. }))
+}
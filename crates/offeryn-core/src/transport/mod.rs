@@ -1,4 +1,56 @@
 mod sse;
 mod stdio;
+mod streamable_http;
+mod websocket;
 pub use sse::SseTransport;
 pub use stdio::StdioTransport;
+pub use streamable_http::{StreamableHttpTransport, SESSION_ID_HEADER};
+pub use websocket::WebSocketTransport;
+
+use crate::Session;
+use jsonrpc_core::{Call, Request as JsonRpcRequest, Response as JsonRpcResponse};
+use serde::Deserialize;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Default limit on concurrently executing requests per connection.
+const DEFAULT_MAX_IN_FLIGHT: usize = 32;
+
+/// Anything a client may send: a request or notification, or its response to
+/// a request the server sent through [`Session::request`](crate::Session::request).
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ClientMessage {
+    // Tried first, since `Call::Invalid` would accept any object with an id
+    Response(JsonRpcResponse),
+    Request(JsonRpcRequest),
+}
+
+/// Wait for one of a connection's in-flight slots. A method call is registered
+/// with the session before it queues, so a cancellation sent while it waits
+/// still finds it; the future resolves to `None` if that happens.
+fn queue_request(
+    session: Arc<Session>,
+    in_flight: Arc<Semaphore>,
+    request: &JsonRpcRequest,
+) -> impl Future<Output = Option<OwnedSemaphorePermit>> + Send + 'static {
+    let queued = match request {
+        JsonRpcRequest::Single(Call::MethodCall(call)) => {
+            Some((call.id.clone(), session.begin_request(&call.id)))
+        }
+        _ => None,
+    };
+    async move {
+        let Some((id, cancellation)) = queued else {
+            return in_flight.acquire_owned().await.ok();
+        };
+        tokio::select! {
+            permit = in_flight.acquire_owned() => permit.ok(),
+            _ = cancellation.cancelled() => {
+                session.end_request(&id);
+                None
+            }
+        }
+    }
+}
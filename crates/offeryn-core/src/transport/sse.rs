@@ -1,14 +1,20 @@
+use super::ClientMessage;
 use crate::McpServer;
 use async_stream::stream;
 use axum::{
     extract::{Json, Query},
     http::StatusCode,
-    response::sse::{Event, Sse},
+    response::{
+        sse::{Event, Sse},
+        IntoResponse, Response,
+    },
     routing::{get, post},
     Extension, Router,
 };
 use futures::stream::Stream;
-use jsonrpc_core::{Output, Request as JsonRpcRequest, Response as JsonRpcResponse};
+use jsonrpc_core::{
+    Call, Failure, Id, Output, Request as JsonRpcRequest, Response as JsonRpcResponse, Version,
+};
 use std::convert::Infallible;
 use std::{
     collections::HashMap,
@@ -16,12 +22,17 @@ use std::{
 };
 use tokio::sync::mpsc;
 use tracing::{error, info, warn};
-use uuid::Uuid;
 
 pub struct SseTransport {
     connections: HashMap<String, mpsc::Sender<Result<Event, Infallible>>>,
 }
 
+impl Default for SseTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SseTransport {
     pub fn new() -> Self {
         info!("Creating new SSE transport");
@@ -38,9 +49,10 @@ impl SseTransport {
             .route(
                 "/sse",
                 get(
-                    |Extension(state): Extension<Arc<Mutex<SseTransport>>>| async move {
+                    |Extension(state): Extension<Arc<Mutex<SseTransport>>>,
+                     Extension(server): Extension<Arc<McpServer>>| async move {
                         info!("New SSE connection request received");
-                        Self::sse_handler(state).await
+                        Self::sse_handler(state, server).await
                     },
                 ),
             )
@@ -50,7 +62,7 @@ impl SseTransport {
                     |Query(params): Query<HashMap<String, String>>,
                      Extension(state): Extension<Arc<Mutex<SseTransport>>>,
                      Extension(server): Extension<Arc<McpServer>>,
-                     Json(request): Json<JsonRpcRequest>| async move {
+                     Json(message): Json<ClientMessage>| async move {
                         let session_id = match params.get("sessionId") {
                             Some(id) => id,
                             None => {
@@ -64,7 +76,7 @@ impl SseTransport {
                             "Received JSON-RPC request"
                         );
 
-                        Self::message_handler(session_id.clone(), state, server, request).await
+                        Self::message_handler(session_id.clone(), state, server, message).await
                     },
                 ),
             )
@@ -82,9 +94,11 @@ impl SseTransport {
 
     async fn sse_handler(
         state: Arc<Mutex<SseTransport>>,
+        server: Arc<McpServer>,
     ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
         let (tx, mut rx) = mpsc::channel(100);
-        let session_id = Uuid::new_v4().to_string();
+        let (notify_tx, mut notify_rx) = mpsc::channel::<Call>(100);
+        let session_id = server.connect(notify_tx).id().to_string();
 
         info!(
             session_id = %session_id,
@@ -101,7 +115,14 @@ impl SseTransport {
             );
         }
 
+        let guard = SessionGuard {
+            session_id: session_id.clone(),
+            state,
+            server,
+        };
+
         let stream = stream! {
+            let _guard = guard;
             info!(
                 session_id = %session_id,
                 "Sending endpoint URL"
@@ -116,7 +137,16 @@ impl SseTransport {
                 session_id = %session_id,
                 "Starting event stream"
             );
-            while let Some(event) = rx.recv().await {
+            loop {
+                let event = tokio::select! {
+                    event = rx.recv() => event,
+                    Some(call) = notify_rx.recv() => Some(Ok(Event::default()
+                        .event("message")
+                        .data(serde_json::to_string(&call).unwrap_or_default()))),
+                };
+                let Some(event) = event else {
+                    break;
+                };
                 info!(
                     session_id = %session_id,
                     "Sending SSE event"
@@ -140,8 +170,8 @@ impl SseTransport {
         session_id: String,
         state: Arc<Mutex<SseTransport>>,
         server: Arc<McpServer>,
-        request: JsonRpcRequest,
-    ) -> Result<Json<JsonRpcResponse>, StatusCode> {
+        message: ClientMessage,
+    ) -> Result<Response, StatusCode> {
         // Get the sender from the state
         let tx = {
             let state = state.lock().unwrap();
@@ -165,56 +195,113 @@ impl SseTransport {
             })?
         };
 
-        // Process request with server
-        let response = server.handle_request(request).await.map_err(|e| {
-            error!(
+        let session = server.session(&session_id).ok_or_else(|| {
+            warn!(
                 session_id = %session_id,
-                error = %e,
-                "Server request handler failed"
+                "Session not registered with server"
             );
-            StatusCode::INTERNAL_SERVER_ERROR
+            StatusCode::NOT_FOUND
         })?;
 
-        // Send response through SSE channel if it's a successful response
-        if let JsonRpcResponse::Single(Output::Success(_)) = &response {
-            // Ensure we send a proper JSON-RPC message
-            let event =
-                Event::default()
-                    .event("message")
-                    .data(serde_json::to_string(&response).map_err(|e| {
-                        error!(
-                            session_id = %session_id,
-                            error = %e,
-                            "Failed to serialize response"
-                        );
-                        StatusCode::INTERNAL_SERVER_ERROR
-                    })?);
+        let request = match message {
+            ClientMessage::Request(request) => request,
+            ClientMessage::Response(response) => {
+                info!(
+                    session_id = %session_id,
+                    "Received response to server request"
+                );
+                session.handle_response(response);
+                return Ok(StatusCode::ACCEPTED.into_response());
+            }
+        };
 
-            info!(
-                session_id = %session_id,
-                "Sending JSON-RPC response through SSE"
-            );
+        let id = match &request {
+            JsonRpcRequest::Single(Call::MethodCall(call)) => Some(call.id.clone()),
+            JsonRpcRequest::Single(Call::Notification(_)) => None,
+            _ => Some(Id::Null),
+        };
 
-            tx.send(Ok(event)).await.map_err(|e| {
+        // Process request with server
+        let response = match server.handle_session_request(&session, request).await {
+            Ok(response) => response,
+            Err(e) => {
                 error!(
                     session_id = %session_id,
                     error = %e,
-                    "Failed to send response through SSE channel"
+                    "Server request handler failed"
                 );
-                StatusCode::INTERNAL_SERVER_ERROR
-            })?;
-        } else {
+                // The client is waiting on the stream, so it needs a reply there
+                id.map(|id| {
+                    JsonRpcResponse::Single(Output::Failure(Failure {
+                        jsonrpc: Some(Version::V2),
+                        error: e.into(),
+                        id,
+                    }))
+                })
+            }
+        };
+
+        // Notifications have no response to deliver
+        let Some(response) = response else {
             info!(
                 session_id = %session_id,
-                "Skipping SSE for notification or error response"
+                "Notification accepted"
             );
-        }
+            return Ok(StatusCode::ACCEPTED.into_response());
+        };
+
+        // Ensure we send a proper JSON-RPC message
+        let event =
+            Event::default()
+                .event("message")
+                .data(serde_json::to_string(&response).map_err(|e| {
+                    error!(
+                        session_id = %session_id,
+                        error = %e,
+                        "Failed to serialize response"
+                    );
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?);
+
+        info!(
+            session_id = %session_id,
+            "Sending JSON-RPC response through SSE"
+        );
+
+        tx.send(Ok(event)).await.map_err(|e| {
+            error!(
+                session_id = %session_id,
+                error = %e,
+                "Failed to send response through SSE channel"
+            );
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
 
         info!(
             session_id = %session_id,
             "Request completed successfully"
         );
-        Ok(Json(response))
+        Ok(Json(response).into_response())
+    }
+}
+
+/// Tears down a session once its SSE stream is dropped by the client.
+struct SessionGuard {
+    session_id: String,
+    state: Arc<Mutex<SseTransport>>,
+    server: Arc<McpServer>,
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.connections.remove(&self.session_id);
+        }
+        self.server.disconnect(&self.session_id);
+        info!(
+            session_id = %self.session_id,
+            "Removed SSE connection"
+        );
     }
 }
 
@@ -222,7 +309,7 @@ impl SseTransport {
 mod tests {
     use super::*;
     use crate::McpServer;
-    use jsonrpc_core::{Call, Id, MethodCall, Params, Version};
+    use jsonrpc_core::{MethodCall, Params};
     use offeryn_derive::tool;
     use serde_json::{json, Value};
 
@@ -285,7 +372,7 @@ mod tests {
         }));
 
         let response = server.handle_request(request).await.unwrap();
-        if let JsonRpcResponse::Single(Output::Success(success)) = response {
+        if let Some(JsonRpcResponse::Single(Output::Success(success))) = response {
             let result: Value = success.result;
             let content = result.get("content").unwrap().as_array().unwrap();
             let text = content[0].get("text").unwrap().as_str().unwrap();
@@ -314,7 +401,7 @@ mod tests {
         }));
 
         let response = server.handle_request(request).await.unwrap();
-        if let JsonRpcResponse::Single(Output::Success(success)) = response {
+        if let Some(JsonRpcResponse::Single(Output::Success(success))) = response {
             let result: Value = success.result;
             let content = result.get("content").unwrap().as_array().unwrap();
             let text = content[0].get("text").unwrap().as_str().unwrap();
@@ -343,7 +430,7 @@ mod tests {
         }));
 
         let response = server.handle_request(request).await.unwrap();
-        if let JsonRpcResponse::Single(Output::Success(success)) = response {
+        if let Some(JsonRpcResponse::Single(Output::Success(success))) = response {
             let result: Value = success.result;
             let content = result.get("content").unwrap().as_array().unwrap();
             let text = content[0].get("text").unwrap().as_str().unwrap();
@@ -364,4 +451,40 @@ mod tests {
         // Create the router
         let _app = SseTransport::create_router(server);
     }
+
+    #[tokio::test]
+    async fn test_handler_error_reaches_stream() {
+        let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+        let (notify_tx, _notify_rx) = mpsc::channel(10);
+        let session_id = server.connect(notify_tx).id().to_string();
+        let (tx, mut rx) = mpsc::channel(10);
+        let state = Arc::new(Mutex::new(SseTransport::new()));
+        state
+            .lock()
+            .unwrap()
+            .connections
+            .insert(session_id.clone(), tx);
+
+        let message = serde_json::from_value(json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "method": "initialize",
+            "params": { "protocolVersion": 5 }
+        }))
+        .unwrap();
+        let response = SseTransport::message_handler(session_id, state, server, message)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["id"], 7);
+        assert_eq!(body["error"]["code"], -32602);
+
+        // The same failure is delivered on the event stream
+        let event = rx.recv().await.unwrap().unwrap();
+        assert!(format!("{:?}", event).contains("-32602"));
+    }
 }
@@ -1,11 +1,12 @@
-use crate::McpServer;
+use super::{queue_request, ClientMessage, DEFAULT_MAX_IN_FLIGHT};
+use crate::{McpServer, Session};
 use jsonrpc_core::{Call, Error, Failure, Id, Output, Request, Response, Version};
 use std::sync::Arc;
 use tokio::{
     io::{
         stdin, stdout, AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, BufWriter,
     },
-    sync::mpsc,
+    sync::{mpsc, Semaphore},
 };
 
 pub struct StdioTransport<R, W>
@@ -16,6 +17,7 @@ where
     server: Arc<McpServer>,
     stdin: R,
     stdout: W,
+    max_in_flight: usize,
 }
 
 impl StdioTransport<tokio::io::Stdin, tokio::io::Stdout> {
@@ -24,6 +26,7 @@ impl StdioTransport<tokio::io::Stdin, tokio::io::Stdout> {
             server,
             stdin: stdin(),
             stdout: stdout(),
+            max_in_flight: DEFAULT_MAX_IN_FLIGHT,
         }
     }
 }
@@ -38,9 +41,18 @@ where
             server,
             stdin,
             stdout,
+            max_in_flight: DEFAULT_MAX_IN_FLIGHT,
         }
     }
 
+    /// Limit how many requests may execute at once. Requests beyond the limit
+    /// wait for a free slot; the transport keeps reading meanwhile, so queued
+    /// requests can still be cancelled.
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight.max(1);
+        self
+    }
+
     async fn read_message<RR: AsyncRead + Unpin>(
         reader: &mut BufReader<RR>,
     ) -> Result<Vec<u8>, std::io::Error> {
@@ -66,15 +78,24 @@ where
     }
 
     pub async fn run(self) -> Result<(), Box<dyn std::error::Error>> {
-        let (tx, mut rx) = mpsc::channel(100);
+        let (tx, mut rx) = mpsc::channel::<Response>(100);
+        let (notify_tx, mut notify_rx) = mpsc::channel::<Call>(100);
+        let session = self.server.connect(notify_tx);
         let mut reader = BufReader::new(self.stdin);
+        let in_flight = Arc::new(Semaphore::new(self.max_in_flight));
 
         let response_handler = tokio::spawn({
             let mut writer = BufWriter::new(self.stdout);
             async move {
-                while let Some(response) = rx.recv().await {
-                    let response_json = serde_json::to_vec(&response)?;
-                    Self::write_message(&mut writer, &response_json).await?;
+                loop {
+                    let message = tokio::select! {
+                        response = rx.recv() => match response {
+                            Some(response) => serde_json::to_vec(&response)?,
+                            None => break,
+                        },
+                        Some(notification) = notify_rx.recv() => serde_json::to_vec(&notification)?,
+                    };
+                    Self::write_message(&mut writer, &message).await?;
                 }
                 Ok::<_, std::io::Error>(())
             }
@@ -87,8 +108,12 @@ where
                 Err(_) => continue,
             };
 
-            let request: Request = match serde_json::from_slice(&message) {
-                Ok(req) => req,
+            let request = match serde_json::from_slice(&message) {
+                Ok(ClientMessage::Request(req)) => req,
+                Ok(ClientMessage::Response(response)) => {
+                    session.handle_response(response);
+                    continue;
+                }
                 Err(_) => {
                     let id = serde_json::from_slice::<serde_json::Value>(&message)
                         .ok()
@@ -106,41 +131,71 @@ where
                 }
             };
 
-            match self.server.handle_request(request.clone()).await {
-                Ok(response) => {
-                    if tx.send(response).await.is_err() {
-                        break;
-                    }
-                }
-                Err(_) => {
-                    let id = match &request {
-                        Request::Single(Call::MethodCall(m)) => m.id.clone(),
-                        Request::Single(Call::Notification(_)) => Id::Num(0),
-                        _ => Id::Num(0),
-                    };
-                    let error_response = Response::Single(Output::Failure(Failure {
-                        jsonrpc: Some(Version::V2),
-                        error: Error::internal_error(),
-                        id,
-                    }));
-                    if tx.send(error_response).await.is_err() {
-                        break;
-                    }
-                }
+            // Notifications are cheap and may carry cancellations, so they are
+            // handled inline. Calls wait for a free slot on their own task so
+            // the loop keeps reading responses and cancellations meanwhile.
+            if matches!(request, Request::Single(Call::Notification(_))) {
+                Self::dispatch(self.server.clone(), session.clone(), request, tx.clone()).await;
+                continue;
             }
+
+            let slot = queue_request(session.clone(), in_flight.clone(), &request);
+            let server = self.server.clone();
+            let session = session.clone();
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let Some(_permit) = slot.await else {
+                    return;
+                };
+                Self::dispatch(server, session, request, tx).await;
+            });
         }
 
+        // Nothing can answer a server request after EOF, so fail those and let
+        // running calls finish and write their responses before disconnecting.
+        session.close_requests();
         drop(tx);
         let _ = response_handler.await?;
+        self.server.disconnect(session.id());
         Ok(())
     }
+
+    async fn dispatch(
+        server: Arc<McpServer>,
+        session: Arc<Session>,
+        request: Request,
+        tx: mpsc::Sender<Response>,
+    ) {
+        let response = match server
+            .handle_session_request(&session, request.clone())
+            .await
+        {
+            Ok(Some(response)) => response,
+            // Notifications are never answered
+            Ok(None) => return,
+            Err(e) => {
+                let id = match &request {
+                    Request::Single(Call::MethodCall(m)) => m.id.clone(),
+                    Request::Single(Call::Notification(_)) => return,
+                    _ => Id::Null,
+                };
+                Response::Single(Output::Failure(Failure {
+                    jsonrpc: Some(Version::V2),
+                    error: e.into(),
+                    id,
+                }))
+            }
+        };
+        let _ = tx.send(response).await;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use offeryn_derive::tool;
-    use serde_json::json;
+    use offeryn_types::{Content, CreateMessageRequest, Role, SamplingMessage, ToolContext};
+    use serde_json::{json, Value};
     use tokio::io::{duplex, DuplexStream};
 
     #[derive(Default)]
@@ -151,6 +206,101 @@ mod tests {
         async fn add(&self, a: i64, b: i64) -> Result<i64, String> {
             Ok(a + b)
         }
+
+        async fn wait(&self, ms: u64) -> Result<u64, String> {
+            tokio::time::sleep(std::time::Duration::from_millis(ms)).await;
+            Ok(ms)
+        }
+    }
+
+    #[derive(Default)]
+    struct Writer {}
+
+    #[tool]
+    impl Writer {
+        async fn haiku(&self, ctx: &ToolContext, topic: String) -> Result<String, String> {
+            let result = ctx
+                .create_message(CreateMessageRequest {
+                    messages: vec![SamplingMessage {
+                        role: Role::User,
+                        content: Content::text(format!("Write a haiku about {}", topic)),
+                    }],
+                    max_tokens: 100,
+                    ..Default::default()
+                })
+                .await?;
+            match result.content {
+                Content::Text { text, .. } => Ok(text),
+                _ => Err("Expected text content".to_string()),
+            }
+        }
+    }
+
+    /// Send a slow call followed by a fast one and return the response ids in
+    /// the order they were written.
+    async fn response_order(max_in_flight: usize) -> Vec<Id> {
+        let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+        server.register_tools(Calculator::default()).await;
+
+        let (client_reader, server_writer) = duplex(1024);
+        let (server_reader, client_writer) = duplex(1024);
+
+        let transport = StdioTransport::with_streams(server, server_reader, server_writer)
+            .with_max_in_flight(max_in_flight);
+        let server_task = tokio::spawn(async move {
+            transport.run().await.unwrap();
+        });
+
+        let requests = [
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": { "name": "calculator_wait", "arguments": { "ms": 200 } }
+            }),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": { "name": "calculator_add", "arguments": { "a": 2, "b": 3 } }
+            }),
+        ];
+
+        let mut client_writer = BufWriter::new(client_writer);
+        let mut client_reader = BufReader::new(client_reader);
+        for request in &requests {
+            StdioTransport::<DuplexStream, DuplexStream>::write_message(
+                &mut client_writer,
+                &serde_json::to_vec(request).unwrap(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let mut ids = Vec::new();
+        for _ in &requests {
+            let response_bytes =
+                StdioTransport::<DuplexStream, DuplexStream>::read_message(&mut client_reader)
+                    .await
+                    .unwrap();
+            match serde_json::from_slice(&response_bytes).unwrap() {
+                Response::Single(Output::Success(success)) => ids.push(success.id),
+                _ => panic!("Expected successful response"),
+            }
+        }
+
+        server_task.abort();
+        ids
+    }
+
+    #[tokio::test]
+    async fn test_out_of_order_responses() {
+        assert_eq!(response_order(4).await, vec![Id::Num(2), Id::Num(1)]);
+    }
+
+    #[tokio::test]
+    async fn test_max_in_flight() {
+        assert_eq!(response_order(1).await, vec![Id::Num(1), Id::Num(2)]);
     }
 
     #[tokio::test]
@@ -208,4 +358,376 @@ mod tests {
             _ => panic!("Expected successful response"),
         }
     }
+
+    #[tokio::test]
+    async fn test_resource_updated_notification() {
+        let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+
+        let (client_reader, server_writer) = duplex(1024);
+        let (server_reader, client_writer) = duplex(1024);
+
+        let transport = StdioTransport::with_streams(server.clone(), server_reader, server_writer);
+        let server_task = tokio::spawn(async move {
+            transport.run().await.unwrap();
+        });
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "resources/subscribe",
+            "params": { "uri": "file:///var/log/app.log" }
+        });
+
+        let mut client_writer = BufWriter::new(client_writer);
+        let mut client_reader = BufReader::new(client_reader);
+        StdioTransport::<DuplexStream, DuplexStream>::write_message(
+            &mut client_writer,
+            &serde_json::to_vec(&request).unwrap(),
+        )
+        .await
+        .unwrap();
+        let response_bytes =
+            StdioTransport::<DuplexStream, DuplexStream>::read_message(&mut client_reader)
+                .await
+                .unwrap();
+        let response: Response = serde_json::from_slice(&response_bytes).unwrap();
+        assert!(matches!(response, Response::Single(Output::Success(_))));
+
+        server
+            .notify_resource_updated("file:///var/log/app.log")
+            .await;
+
+        let notification_bytes =
+            StdioTransport::<DuplexStream, DuplexStream>::read_message(&mut client_reader)
+                .await
+                .unwrap();
+        let notification: serde_json::Value = serde_json::from_slice(&notification_bytes).unwrap();
+
+        server_task.abort();
+
+        assert_eq!(notification["method"], "notifications/resources/updated");
+        assert_eq!(notification["params"]["uri"], "file:///var/log/app.log");
+        assert!(notification.get("id").is_none());
+    }
+
+    async fn send(writer: &mut BufWriter<DuplexStream>, message: Value) {
+        StdioTransport::<DuplexStream, DuplexStream>::write_message(
+            writer,
+            &serde_json::to_vec(&message).unwrap(),
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn read(reader: &mut BufReader<DuplexStream>) -> Value {
+        let bytes = StdioTransport::<DuplexStream, DuplexStream>::read_message(reader)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sampling_round_trip() {
+        let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+        server.register_tools(Writer::default()).await;
+
+        let (client_reader, server_writer) = duplex(1024);
+        let (server_reader, client_writer) = duplex(1024);
+
+        let transport = StdioTransport::with_streams(server, server_reader, server_writer);
+        let server_task = tokio::spawn(async move {
+            transport.run().await.unwrap();
+        });
+
+        let mut client_writer = BufWriter::new(client_writer);
+        let mut client_reader = BufReader::new(client_reader);
+
+        send(
+            &mut client_writer,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": offeryn_types::LATEST_PROTOCOL_VERSION,
+                    "capabilities": { "sampling": {} },
+                    "clientInfo": { "name": "test-client", "version": "1.0.0" }
+                }
+            }),
+        )
+        .await;
+        let initialized = read(&mut client_reader).await;
+        assert_eq!(initialized["id"], 1);
+
+        send(
+            &mut client_writer,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": { "name": "writer_haiku", "arguments": { "topic": "rust" } }
+            }),
+        )
+        .await;
+
+        let request = read(&mut client_reader).await;
+        assert_eq!(request["method"], "sampling/createMessage");
+        assert_eq!(request["params"]["maxTokens"], 100);
+        assert_eq!(
+            request["params"]["messages"][0]["content"]["text"],
+            "Write a haiku about rust"
+        );
+
+        send(
+            &mut client_writer,
+            json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {
+                    "role": "assistant",
+                    "content": { "type": "text", "text": "Borrowed, not owned" },
+                    "model": "test-model"
+                }
+            }),
+        )
+        .await;
+
+        let response = read(&mut client_reader).await;
+        server_task.abort();
+
+        assert_eq!(response["id"], 2);
+        assert_eq!(
+            response["result"]["content"][0]["text"],
+            "Borrowed, not owned"
+        );
+    }
+
+    /// Start a transport serving [`Writer`] and run the `initialize` handshake
+    /// with a client that supports sampling.
+    async fn sampling_client(
+        max_in_flight: usize,
+    ) -> (
+        BufWriter<DuplexStream>,
+        BufReader<DuplexStream>,
+        tokio::task::JoinHandle<()>,
+    ) {
+        let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+        server.register_tools(Writer::default()).await;
+        server.register_tools(Calculator::default()).await;
+
+        let (client_reader, server_writer) = duplex(4096);
+        let (server_reader, client_writer) = duplex(4096);
+        let transport = StdioTransport::with_streams(server, server_reader, server_writer)
+            .with_max_in_flight(max_in_flight);
+        let server_task = tokio::spawn(async move {
+            transport.run().await.unwrap();
+        });
+
+        let mut client_writer = BufWriter::new(client_writer);
+        let mut client_reader = BufReader::new(client_reader);
+        send(
+            &mut client_writer,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": offeryn_types::LATEST_PROTOCOL_VERSION,
+                    "capabilities": { "sampling": {} },
+                    "clientInfo": { "name": "test-client", "version": "1.0.0" }
+                }
+            }),
+        )
+        .await;
+        assert_eq!(read(&mut client_reader).await["id"], 1);
+        (client_writer, client_reader, server_task)
+    }
+
+    #[tokio::test]
+    async fn test_sampling_with_single_slot() {
+        let (mut client_writer, mut client_reader, server_task) = sampling_client(1).await;
+
+        // The second call queues behind the first, which waits on the client
+        send(
+            &mut client_writer,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": { "name": "writer_haiku", "arguments": { "topic": "rust" } }
+            }),
+        )
+        .await;
+        send(
+            &mut client_writer,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 3,
+                "method": "tools/call",
+                "params": { "name": "calculator_add", "arguments": { "a": 2, "b": 3 } }
+            }),
+        )
+        .await;
+
+        let exchange = async {
+            let request = read(&mut client_reader).await;
+            assert_eq!(request["method"], "sampling/createMessage");
+            send(
+                &mut client_writer,
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "result": {
+                        "role": "assistant",
+                        "content": { "type": "text", "text": "Borrowed, not owned" },
+                        "model": "test-model"
+                    }
+                }),
+            )
+            .await;
+            let first = read(&mut client_reader).await;
+            let second = read(&mut client_reader).await;
+            (first, second)
+        };
+        let (first, second) = tokio::time::timeout(std::time::Duration::from_secs(5), exchange)
+            .await
+            .expect("transport stopped reading while all slots were busy");
+        server_task.abort();
+
+        assert_eq!(first["id"], 2);
+        assert_eq!(first["result"]["content"][0]["text"], "Borrowed, not owned");
+        assert_eq!(second["id"], 3);
+        assert_eq!(second["result"]["content"][0]["text"], "5");
+    }
+
+    #[tokio::test]
+    async fn test_eof_fails_pending_sampling() {
+        let (mut client_writer, mut client_reader, server_task) = sampling_client(4).await;
+
+        send(
+            &mut client_writer,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": { "name": "writer_haiku", "arguments": { "topic": "rust" } }
+            }),
+        )
+        .await;
+        let request = read(&mut client_reader).await;
+        assert_eq!(request["method"], "sampling/createMessage");
+
+        // Close stdin without answering; the transport must still shut down
+        drop(client_writer);
+        tokio::time::timeout(std::time::Duration::from_secs(5), server_task)
+            .await
+            .expect("transport did not shut down after EOF")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cancel_queued_request() {
+        let (mut client_writer, mut client_reader, server_task) = sampling_client(1).await;
+
+        // Occupy the only slot, then queue a call and cancel it while it waits
+        for (id, ms) in [(2, 200), (3, 0)] {
+            send(
+                &mut client_writer,
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "method": "tools/call",
+                    "params": { "name": "calculator_wait", "arguments": { "ms": ms } }
+                }),
+            )
+            .await;
+        }
+        send(
+            &mut client_writer,
+            json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/cancelled",
+                "params": { "requestId": 3 }
+            }),
+        )
+        .await;
+        send(
+            &mut client_writer,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 4,
+                "method": "tools/call",
+                "params": { "name": "calculator_add", "arguments": { "a": 2, "b": 3 } }
+            }),
+        )
+        .await;
+
+        // The cancelled call is never answered
+        assert_eq!(read(&mut client_reader).await["id"], 2);
+        assert_eq!(read(&mut client_reader).await["id"], 4);
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_eof_waits_for_running_calls() {
+        let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+        server.register_tools(Calculator::default()).await;
+        let (client_reader, server_writer) = duplex(1024);
+        let (server_reader, client_writer) = duplex(1024);
+        let transport = StdioTransport::with_streams(server, server_reader, server_writer);
+        let server_task = tokio::spawn(async move {
+            transport.run().await.unwrap();
+        });
+
+        let mut client_writer = BufWriter::new(client_writer);
+        let mut client_reader = BufReader::new(client_reader);
+        send(
+            &mut client_writer,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": { "name": "calculator_wait", "arguments": { "ms": 50 } }
+            }),
+        )
+        .await;
+        drop(client_writer);
+
+        let response =
+            tokio::time::timeout(std::time::Duration::from_secs(5), read(&mut client_reader))
+                .await
+                .expect("no response after EOF");
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"]["content"][0]["text"], "50");
+        server_task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_handler_errors_keep_their_code() {
+        let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+        let (client_reader, server_writer) = duplex(1024);
+        let (server_reader, client_writer) = duplex(1024);
+        let transport = StdioTransport::with_streams(server, server_reader, server_writer);
+        let server_task = tokio::spawn(async move {
+            transport.run().await.unwrap();
+        });
+
+        let mut client_writer = BufWriter::new(client_writer);
+        let mut client_reader = BufReader::new(client_reader);
+        send(
+            &mut client_writer,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 7,
+                "method": "initialize",
+                "params": { "protocolVersion": 5 }
+            }),
+        )
+        .await;
+        let response = read(&mut client_reader).await;
+        server_task.abort();
+
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["error"]["code"], -32602);
+    }
 }
@@ -0,0 +1,1086 @@
+use super::ClientMessage;
+use crate::{McpServer, Session};
+use async_stream::stream;
+use axum::{
+    body::Bytes,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::post,
+    Extension, Json, Router,
+};
+use jsonrpc_core::{
+    Call, ErrorCode, Failure, Id, Output, Request as JsonRpcRequest, Response as JsonRpcResponse,
+    Version,
+};
+use std::convert::Infallible;
+use std::time::{Duration, Instant};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::atomic::{AtomicUsize, Ordering},
+    sync::{Arc, Mutex, Weak},
+};
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+
+pub const SESSION_ID_HEADER: &str = "mcp-session-id";
+
+/// How long a session may go without requests or an open stream before it is dropped.
+const DEFAULT_SESSION_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+/// Messages kept for a session while it has no stream open.
+const BACKLOG_LIMIT: usize = 100;
+
+struct StreamableSession {
+    session: Arc<Session>,
+    outbox: Arc<Outbox>,
+}
+
+/// Delivers a session's server-initiated messages that don't belong to a
+/// request, and tracks when the session was last in use.
+struct Outbox {
+    /// The standalone GET stream
+    standalone: Mutex<Option<mpsc::Sender<Call>>>,
+    /// Messages that arrived while no stream was open
+    backlog: Mutex<VecDeque<Call>>,
+    open_requests: AtomicUsize,
+    last_seen: Mutex<Instant>,
+}
+
+impl Outbox {
+    fn new() -> Self {
+        Self {
+            standalone: Mutex::new(None),
+            backlog: Mutex::new(VecDeque::new()),
+            open_requests: AtomicUsize::new(0),
+            last_seen: Mutex::new(Instant::now()),
+        }
+    }
+
+    fn touch(&self) {
+        *self.last_seen.lock().unwrap() = Instant::now();
+    }
+
+    /// The GET stream, if the client has one open.
+    fn stream(&self) -> Option<mpsc::Sender<Call>> {
+        self.standalone
+            .lock()
+            .unwrap()
+            .as_ref()
+            .filter(|tx| !tx.is_closed())
+            .cloned()
+    }
+
+    /// Hand a message to an open stream without ever waiting on the client.
+    fn deliver(&self, session: &Session, call: Call) {
+        let call = match self.stream() {
+            Some(tx) => match tx.try_send(call) {
+                Ok(()) => return,
+                Err(e) => e.into_inner(),
+            },
+            None => call,
+        };
+        // Held for the next stream: clients usually open the GET stream only
+        // after `notifications/initialized`, which is when roots are requested
+        let evicted = {
+            let mut backlog = self.backlog.lock().unwrap();
+            let evicted = (backlog.len() == BACKLOG_LIMIT)
+                .then(|| backlog.pop_front())
+                .flatten();
+            backlog.push_back(call);
+            evicted
+        };
+        // A request pushed out of the backlog will never be answered, so fail
+        // it rather than leave the caller waiting
+        if let Some(Call::MethodCall(request)) = evicted {
+            warn!(
+                session_id = %session.id(),
+                method = %request.method,
+                "Dropped server request that was never delivered"
+            );
+            session.handle_response(JsonRpcResponse::Single(Output::Failure(Failure {
+                jsonrpc: Some(Version::V2),
+                error: jsonrpc_core::Error {
+                    code: ErrorCode::InternalError,
+                    message: "Client has no open stream".to_string(),
+                    data: None,
+                },
+                id: request.id,
+            })));
+        }
+    }
+
+    fn is_idle(&self, timeout: Duration) -> bool {
+        if self.open_requests.load(Ordering::SeqCst) > 0 || self.stream().is_some() {
+            self.touch();
+            return false;
+        }
+        self.last_seen.lock().unwrap().elapsed() >= timeout
+    }
+}
+
+/// Sends messages tied to the method calls of one POST to that POST's SSE
+/// response, for as long as it is being handled.
+struct RequestRoutes {
+    session: Arc<Session>,
+    ids: Vec<Id>,
+}
+
+impl RequestRoutes {
+    fn new(session: Arc<Session>, request: &JsonRpcRequest, sender: mpsc::Sender<Call>) -> Self {
+        let calls = match request {
+            JsonRpcRequest::Single(call) => std::slice::from_ref(call),
+            JsonRpcRequest::Batch(calls) => calls.as_slice(),
+        };
+        let ids: Vec<Id> = calls
+            .iter()
+            .filter_map(|call| match call {
+                Call::MethodCall(call) => Some(call.id.clone()),
+                _ => None,
+            })
+            .collect();
+        for id in &ids {
+            session.route_request(id.clone(), sender.clone());
+        }
+        Self { session, ids }
+    }
+}
+
+impl Drop for RequestRoutes {
+    fn drop(&mut self) {
+        for id in &self.ids {
+            self.session.unroute_request(id);
+        }
+    }
+}
+
+/// Marks a POST as in progress so its session isn't expired underneath it.
+struct OpenRequest(Arc<Outbox>);
+
+impl OpenRequest {
+    fn new(outbox: Arc<Outbox>) -> Self {
+        outbox.open_requests.fetch_add(1, Ordering::SeqCst);
+        outbox.touch();
+        Self(outbox)
+    }
+}
+
+impl Drop for OpenRequest {
+    fn drop(&mut self) {
+        self.0.open_requests.fetch_sub(1, Ordering::SeqCst);
+        self.0.touch();
+    }
+}
+
+/// The Streamable HTTP transport: a single `/mcp` endpoint where clients POST
+/// JSON-RPC messages, GET a stream of server-initiated messages and DELETE to
+/// end their session. Sessions are identified by the `Mcp-Session-Id` header.
+pub struct StreamableHttpTransport {
+    sessions: HashMap<String, StreamableSession>,
+    session_timeout: Duration,
+}
+
+impl Default for StreamableHttpTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamableHttpTransport {
+    pub fn new() -> Self {
+        info!("Creating new Streamable HTTP transport");
+        Self {
+            sessions: HashMap::new(),
+            session_timeout: DEFAULT_SESSION_TIMEOUT,
+        }
+    }
+
+    /// Drop sessions that have had no requests and no open stream for this
+    /// long. Clients that go away without a DELETE are cleaned up this way.
+    pub fn with_session_timeout(mut self, timeout: Duration) -> Self {
+        self.session_timeout = timeout.max(Duration::from_millis(1));
+        self
+    }
+
+    pub fn create_router(server: Arc<McpServer>) -> Router {
+        Self::new().into_router(server)
+    }
+
+    pub fn into_router(self, server: Arc<McpServer>) -> Router {
+        info!("Creating Streamable HTTP router");
+        let state = Arc::new(Mutex::new(self));
+
+        Router::new()
+            .route(
+                "/mcp",
+                post(
+                    |Extension(state): Extension<Arc<Mutex<StreamableHttpTransport>>>,
+                     Extension(server): Extension<Arc<McpServer>>,
+                     headers: HeaderMap,
+                     body: Bytes| async move {
+                        Self::post_handler(state, server, headers, body).await
+                    },
+                )
+                .get(
+                    |Extension(state): Extension<Arc<Mutex<StreamableHttpTransport>>>,
+                     headers: HeaderMap| async move {
+                        Self::get_handler(state, headers).await
+                    },
+                )
+                .delete(
+                    |Extension(state): Extension<Arc<Mutex<StreamableHttpTransport>>>,
+                     Extension(server): Extension<Arc<McpServer>>,
+                     headers: HeaderMap| async move {
+                        Self::delete_handler(state, server, headers).await
+                    },
+                ),
+            )
+            .fallback(|req: axum::http::Request<axum::body::Body>| async move {
+                error!(
+                    method = %req.method(),
+                    uri = %req.uri(),
+                    "Request to unknown route"
+                );
+                StatusCode::NOT_FOUND
+            })
+            .layer(Extension(state))
+            .layer(Extension(server))
+    }
+
+    fn session_id(headers: &HeaderMap) -> Option<String> {
+        headers
+            .get(SESSION_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    }
+
+    fn accepts(headers: &HeaderMap, mime: &str) -> bool {
+        headers
+            .get_all(header::ACCEPT)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .any(|v| v.contains(mime) || v.contains("*/*"))
+    }
+
+    fn message_event(message: &impl serde::Serialize) -> Result<Event, Infallible> {
+        Ok(Event::default()
+            .event("message")
+            .data(serde_json::to_string(message).unwrap_or_default()))
+    }
+
+    async fn post_handler(
+        state: Arc<Mutex<StreamableHttpTransport>>,
+        server: Arc<McpServer>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> Response {
+        let request = match serde_json::from_slice(&body) {
+            Ok(ClientMessage::Request(request)) => request,
+            Ok(ClientMessage::Response(response)) => {
+                return Self::response_handler(state, &headers, response);
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to parse JSON-RPC request");
+                let failure = JsonRpcResponse::Single(Output::Failure(Failure {
+                    jsonrpc: Some(Version::V2),
+                    error: jsonrpc_core::Error::parse_error(),
+                    id: Id::Null,
+                }));
+                return (StatusCode::BAD_REQUEST, Json(failure)).into_response();
+            }
+        };
+
+        let (session, outbox) = match Self::session_id(&headers) {
+            Some(session_id) => {
+                let state = state.lock().unwrap();
+                match state.sessions.get(&session_id) {
+                    Some(entry) => (entry.session.clone(), entry.outbox.clone()),
+                    None => {
+                        warn!(session_id = %session_id, "Session ID not found");
+                        return StatusCode::NOT_FOUND.into_response();
+                    }
+                }
+            }
+            None => {
+                let is_initialize = matches!(
+                    &request,
+                    JsonRpcRequest::Single(Call::MethodCall(call)) if call.method == "initialize"
+                );
+                if !is_initialize {
+                    warn!("Request without session ID must be initialize");
+                    return StatusCode::BAD_REQUEST.into_response();
+                }
+                Self::open_session(state, server.clone())
+            }
+        };
+        let open = OpenRequest::new(outbox.clone());
+
+        let session_header = HeaderValue::from_str(session.id()).expect("uuid is a valid header");
+        let calls = match &request {
+            JsonRpcRequest::Single(call) => std::slice::from_ref(call),
+            JsonRpcRequest::Batch(calls) => calls.as_slice(),
+        };
+        let has_requests = calls.iter().any(|c| matches!(c, Call::MethodCall(_)));
+        // Tool calls can send progress, logs and sampling requests while they
+        // run, which only an SSE response can carry
+        let sends_messages = calls
+            .iter()
+            .any(|c| matches!(c, Call::MethodCall(call) if call.method == "tools/call"));
+        let accepts_sse = Self::accepts(&headers, "text/event-stream");
+
+        // Bodies carrying only notifications are acknowledged without a response
+        if !has_requests {
+            Self::handle(server, session, request).await;
+            return (StatusCode::ACCEPTED, [(SESSION_ID_HEADER, session_header)]).into_response();
+        }
+
+        if Self::accepts(&headers, "application/json") && !(accepts_sse && sends_messages) {
+            let response = Self::handle(server, session, request).await;
+            return ([(SESSION_ID_HEADER, session_header)], Json(response)).into_response();
+        }
+
+        if accepts_sse {
+            // Messages the request sends while it is handled go out on this
+            // stream, which ends with the response itself.
+            let (tx, mut rx) = mpsc::channel(BACKLOG_LIMIT);
+            let routes = RequestRoutes::new(session.clone(), &request, tx);
+            let stream = stream! {
+                let _open = open;
+                let _routes = routes;
+                let handling = Self::handle(server, session, request);
+                tokio::pin!(handling);
+                loop {
+                    let next = tokio::select! {
+                        response = &mut handling => Err(response),
+                        Some(call) = rx.recv() => Ok(call),
+                    };
+                    match next {
+                        Ok(call) => yield Self::message_event(&call),
+                        Err(response) => {
+                            // Anything sent from here on goes to the session's channel
+                            rx.close();
+                            while let Some(call) = rx.recv().await {
+                                yield Self::message_event(&call);
+                            }
+                            if let Some(response) = response {
+                                yield Self::message_event(&response);
+                            }
+                            break;
+                        }
+                    }
+                }
+            };
+            return ([(SESSION_ID_HEADER, session_header)], Sse::new(stream)).into_response();
+        }
+
+        StatusCode::NOT_ACCEPTABLE.into_response()
+    }
+
+    /// Run a request, turning handler errors into a JSON-RPC failure.
+    async fn handle(
+        server: Arc<McpServer>,
+        session: Arc<Session>,
+        request: JsonRpcRequest,
+    ) -> Option<JsonRpcResponse> {
+        let id = match &request {
+            JsonRpcRequest::Single(Call::MethodCall(call)) => call.id.clone(),
+            _ => Id::Null,
+        };
+        match server.handle_session_request(&session, request).await {
+            Ok(response) => response,
+            Err(e) => {
+                warn!(session_id = %session.id(), error = %e, "Server request handler failed");
+                Some(JsonRpcResponse::Single(Output::Failure(Failure {
+                    jsonrpc: Some(Version::V2),
+                    error: e.into(),
+                    id,
+                })))
+            }
+        }
+    }
+
+    fn open_session(
+        state: Arc<Mutex<StreamableHttpTransport>>,
+        server: Arc<McpServer>,
+    ) -> (Arc<Session>, Arc<Outbox>) {
+        let (notify_tx, notify_rx) = mpsc::channel(BACKLOG_LIMIT);
+        let session = server.connect(notify_tx);
+        let outbox = Arc::new(Outbox::new());
+        let timeout = {
+            let mut state = state.lock().unwrap();
+            state.sessions.insert(
+                session.id().to_string(),
+                StreamableSession {
+                    session: session.clone(),
+                    outbox: outbox.clone(),
+                },
+            );
+            state.session_timeout
+        };
+        info!(session_id = %session.id(), "Created Streamable HTTP session");
+
+        tokio::spawn(Self::forward(
+            state,
+            server,
+            Arc::downgrade(&session),
+            outbox.clone(),
+            notify_rx,
+            timeout,
+        ));
+        (session, outbox)
+    }
+
+    /// Drain a session's server-initiated messages into its GET stream, and
+    /// expire the session once it has been idle for `timeout`.
+    async fn forward(
+        state: Arc<Mutex<StreamableHttpTransport>>,
+        server: Arc<McpServer>,
+        session: Weak<Session>,
+        outbox: Arc<Outbox>,
+        mut notifications: mpsc::Receiver<Call>,
+        timeout: Duration,
+    ) {
+        let Some(session_id) = session.upgrade().map(|s| s.id().to_string()) else {
+            return;
+        };
+        let mut idle_check = tokio::time::interval(timeout / 2);
+        loop {
+            tokio::select! {
+                call = notifications.recv() => match (call, session.upgrade()) {
+                    (Some(call), Some(session)) => outbox.deliver(&session, call),
+                    _ => break,
+                },
+                _ = idle_check.tick() => {
+                    if !state.lock().unwrap().sessions.contains_key(&session_id) {
+                        break;
+                    }
+                    if outbox.is_idle(timeout) {
+                        state.lock().unwrap().sessions.remove(&session_id);
+                        server.disconnect(&session_id);
+                        info!(session_id = %session_id, "Expired idle Streamable HTTP session");
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// The client answering a request the server sent on one of its streams.
+    fn response_handler(
+        state: Arc<Mutex<StreamableHttpTransport>>,
+        headers: &HeaderMap,
+        response: JsonRpcResponse,
+    ) -> Response {
+        let Some(session_id) = Self::session_id(headers) else {
+            warn!("Response without session ID");
+            return StatusCode::BAD_REQUEST.into_response();
+        };
+        let session = match state.lock().unwrap().sessions.get(&session_id) {
+            Some(entry) => {
+                entry.outbox.touch();
+                entry.session.clone()
+            }
+            None => {
+                warn!(session_id = %session_id, "Session ID not found");
+                return StatusCode::NOT_FOUND.into_response();
+            }
+        };
+        session.handle_response(response);
+        StatusCode::ACCEPTED.into_response()
+    }
+
+    async fn get_handler(
+        state: Arc<Mutex<StreamableHttpTransport>>,
+        headers: HeaderMap,
+    ) -> Response {
+        if !Self::accepts(&headers, "text/event-stream") {
+            return StatusCode::METHOD_NOT_ALLOWED.into_response();
+        }
+        let Some(session_id) = Self::session_id(&headers) else {
+            warn!("GET without session ID");
+            return StatusCode::BAD_REQUEST.into_response();
+        };
+
+        let outbox = {
+            let state = state.lock().unwrap();
+            match state.sessions.get(&session_id) {
+                Some(entry) => entry.outbox.clone(),
+                None => {
+                    warn!(session_id = %session_id, "Session ID not found");
+                    return StatusCode::NOT_FOUND.into_response();
+                }
+            }
+        };
+
+        // Only one standalone stream per session at a time.
+        let (tx, mut rx) = mpsc::channel(BACKLOG_LIMIT);
+        {
+            let mut standalone = outbox.standalone.lock().unwrap();
+            if standalone.as_ref().is_some_and(|tx| !tx.is_closed()) {
+                warn!(session_id = %session_id, "Session already has an open stream");
+                return StatusCode::CONFLICT.into_response();
+            }
+            *standalone = Some(tx);
+        }
+        outbox.touch();
+        let backlog: Vec<Call> = outbox.backlog.lock().unwrap().drain(..).collect();
+
+        info!(session_id = %session_id, "Opened server-initiated message stream");
+        let stream = stream! {
+            for call in backlog {
+                yield Self::message_event(&call);
+            }
+            while let Some(call) = rx.recv().await {
+                info!(session_id = %session_id, "Sending SSE event");
+                yield Self::message_event(&call);
+            }
+        };
+        Sse::new(stream)
+            .keep_alive(KeepAlive::default())
+            .into_response()
+    }
+
+    async fn delete_handler(
+        state: Arc<Mutex<StreamableHttpTransport>>,
+        server: Arc<McpServer>,
+        headers: HeaderMap,
+    ) -> Response {
+        let Some(session_id) = Self::session_id(&headers) else {
+            return StatusCode::BAD_REQUEST.into_response();
+        };
+        if state.lock().unwrap().sessions.remove(&session_id).is_none() {
+            warn!(session_id = %session_id, "Session ID not found");
+            return StatusCode::NOT_FOUND.into_response();
+        }
+        server.disconnect(&session_id);
+        info!(session_id = %session_id, "Terminated Streamable HTTP session");
+        StatusCode::OK.into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use offeryn_derive::tool;
+    use offeryn_types::{ToolContext, LATEST_PROTOCOL_VERSION};
+    use serde_json::{json, Value};
+
+    #[derive(Default)]
+    struct Calculator {}
+
+    #[tool]
+    impl Calculator {
+        /// Add two numbers
+        async fn add(&self, a: i64, b: i64) -> i64 {
+            a + b
+        }
+
+        /// Count up to a number, reporting progress along the way
+        async fn count(&self, ctx: &ToolContext, to: u32) -> u32 {
+            for i in 1..=to {
+                ctx.progress(i as f64, Some(to as f64), None).await.unwrap();
+            }
+            to
+        }
+
+        /// Report progress before and after a pause
+        async fn pause(&self, ctx: &ToolContext, ms: u64) -> u64 {
+            ctx.progress(0.0, None, None).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(ms)).await;
+            ctx.progress(1.0, None, None).await.unwrap();
+            ms
+        }
+    }
+
+    /// Every message carried by an SSE response body.
+    async fn events(response: reqwest::Response) -> Vec<Value> {
+        response
+            .text()
+            .await
+            .unwrap()
+            .lines()
+            .filter_map(|line| line.strip_prefix("data: "))
+            .map(|data| serde_json::from_str(data).unwrap())
+            .collect()
+    }
+
+    async fn serve() -> (Arc<McpServer>, String) {
+        serve_with(StreamableHttpTransport::new()).await
+    }
+
+    async fn serve_with(transport: StreamableHttpTransport) -> (Arc<McpServer>, String) {
+        let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+        server.register_tools(Calculator::default()).await;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+        let app = transport.into_router(server.clone());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        (server, url)
+    }
+
+    async fn initialize(http: &reqwest::Client, url: &str) -> String {
+        let response = http
+            .post(url)
+            .header(header::ACCEPT, "application/json, text/event-stream")
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": LATEST_PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": { "name": "test-client", "version": "0.1.0" }
+                }
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let session_id = response.headers()[SESSION_ID_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["result"]["serverInfo"]["name"], "test-server");
+        session_id
+    }
+
+    #[tokio::test]
+    async fn test_session_lifecycle() {
+        let (server, url) = serve().await;
+        let http = reqwest::Client::new();
+
+        // Requests other than initialize need a session
+        let response = http
+            .post(&url)
+            .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let session_id = initialize(&http, &url).await;
+        assert!(server.session(&session_id).is_some());
+
+        // Tool calls are answered on a stream when the client accepts one
+        let response = http
+            .post(&url)
+            .header(SESSION_ID_HEADER, &session_id)
+            .header(header::ACCEPT, "application/json, text/event-stream")
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": { "name": "calculator_add", "arguments": { "a": 2, "b": 3 } }
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/event-stream"
+        );
+        let messages = events(response).await;
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["id"], 2);
+        assert_eq!(messages[0]["result"]["content"][0]["text"], "5");
+
+        // Other requests get plain JSON
+        let response = http
+            .post(&url)
+            .header(SESSION_ID_HEADER, &session_id)
+            .header(header::ACCEPT, "application/json, text/event-stream")
+            .json(&json!({ "jsonrpc": "2.0", "id": 3, "method": "tools/list" }))
+            .send()
+            .await
+            .unwrap();
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["id"], 3);
+
+        // SSE response
+        let response = http
+            .post(&url)
+            .header(SESSION_ID_HEADER, &session_id)
+            .header(header::ACCEPT, "text/event-stream")
+            .json(&json!({ "jsonrpc": "2.0", "id": 3, "method": "tools/list" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/event-stream"
+        );
+        let body = response.text().await.unwrap();
+        let data = body
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .unwrap();
+        let message: Value = serde_json::from_str(data).unwrap();
+        assert_eq!(message["id"], 3);
+        let tools = message["result"]["tools"].as_array().unwrap();
+        assert!(tools.iter().any(|tool| tool["name"] == "calculator_add"));
+
+        // Notifications are accepted without a body
+        let response = http
+            .post(&url)
+            .header(SESSION_ID_HEADER, &session_id)
+            .json(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        // Termination
+        let response = http
+            .delete(&url)
+            .header(SESSION_ID_HEADER, &session_id)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(server.session(&session_id).is_none());
+
+        let response = http
+            .post(&url)
+            .header(SESSION_ID_HEADER, &session_id)
+            .json(&json!({ "jsonrpc": "2.0", "id": 4, "method": "tools/list" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_server_initiated_stream() {
+        use futures::StreamExt;
+
+        let (server, url) = serve().await;
+        let http = reqwest::Client::new();
+        let session_id = initialize(&http, &url).await;
+
+        http.post(&url)
+            .header(SESSION_ID_HEADER, &session_id)
+            .header(header::ACCEPT, "application/json")
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "resources/subscribe",
+                "params": { "uri": "file:///log" }
+            }))
+            .send()
+            .await
+            .unwrap();
+
+        let response = http
+            .get(&url)
+            .header(SESSION_ID_HEADER, &session_id)
+            .header(header::ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // A second stream for the same session is rejected
+        let second = http
+            .get(&url)
+            .header(SESSION_ID_HEADER, &session_id)
+            .header(header::ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(second.status(), StatusCode::CONFLICT);
+
+        server.notify_resource_updated("file:///log").await;
+
+        let mut stream = response.bytes_stream();
+        let mut received = String::new();
+        while !received.contains("\n\n") {
+            let chunk = stream.next().await.unwrap().unwrap();
+            received.push_str(&String::from_utf8_lossy(&chunk));
+        }
+        let data = received
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .unwrap();
+        let message: Value = serde_json::from_str(data).unwrap();
+        assert_eq!(message["method"], "notifications/resources/updated");
+        assert_eq!(message["params"]["uri"], "file:///log");
+    }
+
+    fn count_call(id: u64, to: u32) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "tools/call",
+            "params": {
+                "name": "calculator_count",
+                "arguments": { "to": to },
+                "_meta": { "progressToken": "count" }
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_sse_response_carries_call_messages() {
+        let (_server, url) = serve().await;
+        let http = reqwest::Client::new();
+        let session_id = initialize(&http, &url).await;
+
+        let response = http
+            .post(&url)
+            .header(SESSION_ID_HEADER, &session_id)
+            .header(header::ACCEPT, "application/json, text/event-stream")
+            .json(&count_call(2, 3))
+            .send()
+            .await
+            .unwrap();
+        let messages = events(response).await;
+
+        // Progress for the call arrives on its stream, ahead of the result
+        assert_eq!(messages.len(), 4);
+        for (i, message) in messages[..3].iter().enumerate() {
+            assert_eq!(message["method"], "notifications/progress");
+            assert_eq!(message["params"]["progress"], (i + 1) as f64);
+        }
+        assert_eq!(messages[3]["id"], 2);
+        assert_eq!(messages[3]["result"]["content"][0]["text"], "3");
+    }
+
+    #[tokio::test]
+    async fn test_notifications_without_stream_do_not_block() {
+        use futures::StreamExt;
+
+        let (_server, url) = serve().await;
+        let http = reqwest::Client::new();
+        let session_id = initialize(&http, &url).await;
+
+        // A JSON response has nowhere to put progress, and no GET stream is open
+        let response = tokio::time::timeout(
+            Duration::from_secs(5),
+            http.post(&url)
+                .header(SESSION_ID_HEADER, &session_id)
+                .header(header::ACCEPT, "application/json")
+                .json(&count_call(2, 150))
+                .send(),
+        )
+        .await
+        .expect("call blocked on undelivered notifications")
+        .unwrap();
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["result"]["content"][0]["text"], "150");
+
+        // The most recent notifications are waiting for the next stream
+        let response = http
+            .get(&url)
+            .header(SESSION_ID_HEADER, &session_id)
+            .header(header::ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .unwrap();
+        let mut stream = response.bytes_stream();
+        let mut received = String::new();
+        while !received.contains("\n\n") {
+            let chunk = stream.next().await.unwrap().unwrap();
+            received.push_str(&String::from_utf8_lossy(&chunk));
+        }
+        let data = received
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .unwrap();
+        let message: Value = serde_json::from_str(data).unwrap();
+        assert_eq!(message["params"]["progress"], 51.0);
+    }
+
+    #[tokio::test]
+    async fn test_idle_session_expires() {
+        let transport =
+            StreamableHttpTransport::new().with_session_timeout(Duration::from_millis(100));
+        let (server, url) = serve_with(transport).await;
+        let http = reqwest::Client::new();
+        let session_id = initialize(&http, &url).await;
+        assert!(server.session(&session_id).is_some());
+
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert!(server.session(&session_id).is_none());
+
+        let response = http
+            .post(&url)
+            .header(SESSION_ID_HEADER, &session_id)
+            .json(&json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_messages_reach_their_own_stream() {
+        use futures::StreamExt;
+
+        let (server, url) = serve().await;
+        let http = reqwest::Client::new();
+        let session_id = initialize(&http, &url).await;
+        let post = |body: Value| {
+            http.post(&url)
+                .header(SESSION_ID_HEADER, &session_id)
+                .header(header::ACCEPT, "application/json, text/event-stream")
+                .json(&body)
+                .send()
+        };
+
+        post(json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "resources/subscribe",
+            "params": { "uri": "file:///log" }
+        }))
+        .await
+        .unwrap();
+        let standalone = http
+            .get(&url)
+            .header(SESSION_ID_HEADER, &session_id)
+            .header(header::ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .unwrap();
+
+        // `pause` reports progress after `wait` has opened a newer stream, and
+        // a resource changes while both are open
+        let pause = post(json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "tools/call",
+            "params": {
+                "name": "calculator_pause",
+                "arguments": { "ms": 300 },
+                "_meta": { "progressToken": "pause" }
+            }
+        }));
+        let wait = async {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            let response = post(json!({
+                "jsonrpc": "2.0",
+                "id": 4,
+                "method": "tools/call",
+                "params": {
+                    "name": "calculator_pause",
+                    "arguments": { "ms": 400 }
+                }
+            }))
+            .await;
+            server.notify_resource_updated("file:///log").await;
+            response
+        };
+        let (pause, wait) = tokio::join!(pause, wait);
+        let (pause, wait) = (events(pause.unwrap()).await, events(wait.unwrap()).await);
+
+        assert_eq!(pause.len(), 3);
+        assert_eq!(pause[0]["params"]["progressToken"], "pause");
+        assert_eq!(pause[1]["params"]["progress"], 1.0);
+        assert_eq!(pause[2]["id"], 3);
+        assert_eq!(wait.len(), 1);
+        assert_eq!(wait[0]["id"], 4);
+
+        let mut stream = standalone.bytes_stream();
+        let mut received = String::new();
+        while !received.contains("\n\n") {
+            let chunk = stream.next().await.unwrap().unwrap();
+            received.push_str(&String::from_utf8_lossy(&chunk));
+        }
+        let data = received
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .unwrap();
+        let message: Value = serde_json::from_str(data).unwrap();
+        assert_eq!(message["method"], "notifications/resources/updated");
+    }
+
+    #[tokio::test]
+    async fn test_roots_requested_once_stream_opens() {
+        use futures::StreamExt;
+
+        let (server, url) = serve().await;
+        let http = reqwest::Client::new();
+        let response = http
+            .post(&url)
+            .header(header::ACCEPT, "application/json, text/event-stream")
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": LATEST_PROTOCOL_VERSION,
+                    "capabilities": { "roots": { "listChanged": true } },
+                    "clientInfo": { "name": "test-client", "version": "0.1.0" }
+                }
+            }))
+            .send()
+            .await
+            .unwrap();
+        let session_id = response.headers()[SESSION_ID_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        // The server asks for roots before the client has a stream to hear it
+        let response = http
+            .post(&url)
+            .header(SESSION_ID_HEADER, &session_id)
+            .json(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        let response = http
+            .get(&url)
+            .header(SESSION_ID_HEADER, &session_id)
+            .header(header::ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .unwrap();
+        let mut stream = response.bytes_stream();
+        let mut received = String::new();
+        while !received.contains("\n\n") {
+            let chunk = tokio::time::timeout(Duration::from_secs(2), stream.next())
+                .await
+                .expect("roots/list was never sent")
+                .unwrap()
+                .unwrap();
+            received.push_str(&String::from_utf8_lossy(&chunk));
+        }
+        let data = received
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .unwrap();
+        let request: Value = serde_json::from_str(data).unwrap();
+        assert_eq!(request["method"], "roots/list");
+
+        let response = http
+            .post(&url)
+            .header(SESSION_ID_HEADER, &session_id)
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": { "roots": [{ "uri": "file:///workspace", "name": "workspace" }] }
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        let session = server.session(&session_id).unwrap();
+        for _ in 0..50 {
+            if session.roots().is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let roots = session.roots().expect("roots were not cached");
+        assert_eq!(roots[0].uri, "file:///workspace");
+    }
+}
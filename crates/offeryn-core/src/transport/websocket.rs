@@ -0,0 +1,343 @@
+use super::{queue_request, ClientMessage, DEFAULT_MAX_IN_FLIGHT};
+use crate::{McpServer, Session};
+use axum::{
+    extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    http::StatusCode,
+    response::Response,
+    routing::get,
+    Extension, Router,
+};
+use futures::{SinkExt, StreamExt};
+use jsonrpc_core::{
+    Call, Error, Failure, Id, Output, Request as JsonRpcRequest, Response as JsonRpcResponse,
+    Version,
+};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Semaphore};
+use tracing::{error, info, warn};
+
+/// Runs a full-duplex JSON-RPC session over a WebSocket at `/ws`. Responses and
+/// server-initiated messages share the same socket.
+pub struct WebSocketTransport {
+    max_in_flight: usize,
+}
+
+impl Default for WebSocketTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WebSocketTransport {
+    pub fn new() -> Self {
+        Self {
+            max_in_flight: DEFAULT_MAX_IN_FLIGHT,
+        }
+    }
+
+    /// Limit how many requests may execute at once on each connection.
+    /// Requests beyond the limit wait for a free slot.
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight.max(1);
+        self
+    }
+
+    pub fn create_router(server: Arc<McpServer>) -> Router {
+        Self::new().into_router(server)
+    }
+
+    pub fn into_router(self, server: Arc<McpServer>) -> Router {
+        info!("Creating WebSocket router");
+        let max_in_flight = self.max_in_flight;
+        Router::new()
+            .route(
+                "/ws",
+                get(
+                    move |ws: WebSocketUpgrade, Extension(server): Extension<Arc<McpServer>>| async move {
+                        info!("New WebSocket connection request received");
+                        Self::ws_handler(ws, server, max_in_flight)
+                    },
+                ),
+            )
+            .fallback(|req: axum::http::Request<axum::body::Body>| async move {
+                error!(
+                    method = %req.method(),
+                    uri = %req.uri(),
+                    "Request to unknown route"
+                );
+                StatusCode::NOT_FOUND
+            })
+            .layer(Extension(server))
+    }
+
+    fn ws_handler(ws: WebSocketUpgrade, server: Arc<McpServer>, max_in_flight: usize) -> Response {
+        ws.on_upgrade(move |socket| Self::run_socket(socket, server, max_in_flight))
+    }
+
+    async fn run_socket(socket: WebSocket, server: Arc<McpServer>, max_in_flight: usize) {
+        let (mut sink, mut stream) = socket.split();
+        let (tx, mut rx) = mpsc::channel::<JsonRpcResponse>(100);
+        let (notify_tx, mut notify_rx) = mpsc::channel::<Call>(100);
+        let session = server.connect(notify_tx);
+        let session_id = session.id().to_string();
+        let in_flight = Arc::new(Semaphore::new(max_in_flight));
+        info!(session_id = %session_id, "WebSocket connection established");
+
+        let writer = tokio::spawn({
+            let session_id = session_id.clone();
+            async move {
+                loop {
+                    let message = tokio::select! {
+                        response = rx.recv() => match response {
+                            Some(response) => serde_json::to_string(&response),
+                            None => break,
+                        },
+                        Some(call) = notify_rx.recv() => serde_json::to_string(&call),
+                    };
+                    let message = match message {
+                        Ok(message) => message,
+                        Err(e) => {
+                            error!(session_id = %session_id, error = %e, "Failed to serialize message");
+                            continue;
+                        }
+                    };
+                    if sink.send(Message::Text(message)).await.is_err() {
+                        warn!(session_id = %session_id, "Failed to write to WebSocket");
+                        break;
+                    }
+                }
+            }
+        });
+
+        while let Some(message) = stream.next().await {
+            let text = match message {
+                Ok(Message::Text(text)) => text,
+                Ok(Message::Binary(bytes)) => match String::from_utf8(bytes) {
+                    Ok(text) => text,
+                    Err(_) => {
+                        warn!(session_id = %session_id, "Ignoring non UTF-8 binary frame");
+                        continue;
+                    }
+                },
+                Ok(Message::Close(_)) => break,
+                Ok(_) => continue,
+                Err(e) => {
+                    warn!(session_id = %session_id, error = %e, "WebSocket receive failed");
+                    break;
+                }
+            };
+
+            let request = match serde_json::from_str(&text) {
+                Ok(ClientMessage::Request(request)) => request,
+                Ok(ClientMessage::Response(response)) => {
+                    session.handle_response(response);
+                    continue;
+                }
+                Err(_) => {
+                    let failure = JsonRpcResponse::Single(Output::Failure(Failure {
+                        jsonrpc: Some(Version::V2),
+                        error: Error::parse_error(),
+                        id: Id::Null,
+                    }));
+                    let _ = tx.send(failure).await;
+                    continue;
+                }
+            };
+
+            // Notifications are handled inline so a cancellation can't overtake
+            // the request it cancels. Calls run on their own task so slow tools
+            // don't block the socket.
+            if matches!(request, JsonRpcRequest::Single(Call::Notification(_))) {
+                Self::handle_message(server.clone(), session.clone(), request, tx.clone()).await;
+                continue;
+            }
+
+            let slot = queue_request(session.clone(), in_flight.clone(), &request);
+            let server = server.clone();
+            let session = session.clone();
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let Some(_permit) = slot.await else {
+                    return;
+                };
+                Self::handle_message(server, session, request, tx).await;
+            });
+        }
+
+        server.disconnect(&session_id);
+        drop(tx);
+        let _ = writer.await;
+        info!(session_id = %session_id, "WebSocket connection closed");
+    }
+
+    async fn handle_message(
+        server: Arc<McpServer>,
+        session: Arc<Session>,
+        request: JsonRpcRequest,
+        tx: mpsc::Sender<JsonRpcResponse>,
+    ) {
+        let id = match &request {
+            JsonRpcRequest::Single(Call::MethodCall(call)) => call.id.clone(),
+            _ => Id::Null,
+        };
+
+        let response = match server.handle_session_request(&session, request).await {
+            Ok(Some(response)) => response,
+            // Notifications never get a reply
+            Ok(None) => return,
+            Err(e) => {
+                warn!(session_id = %session.id(), error = %e, "Server request handler failed");
+                JsonRpcResponse::Single(Output::Failure(Failure {
+                    jsonrpc: Some(Version::V2),
+                    error: e.into(),
+                    id,
+                }))
+            }
+        };
+        let _ = tx.send(response).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use offeryn_derive::tool;
+    use serde_json::{json, Value};
+    use tokio_tungstenite::{connect_async, tungstenite};
+
+    #[derive(Default)]
+    struct Calculator {}
+
+    #[tool]
+    impl Calculator {
+        /// Add two numbers
+        async fn add(&self, a: i64, b: i64) -> i64 {
+            a + b
+        }
+
+        /// Wait before answering
+        async fn wait(&self, ms: u64) -> u64 {
+            tokio::time::sleep(std::time::Duration::from_millis(ms)).await;
+            ms
+        }
+    }
+
+    async fn send(
+        socket: &mut tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+        message: Value,
+    ) {
+        socket
+            .send(tungstenite::Message::Text(message.to_string()))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_websocket_session() {
+        let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+        server.register_tools(Calculator::default()).await;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/ws", listener.local_addr().unwrap());
+        let app = WebSocketTransport::create_router(server.clone());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let (mut socket, _) = connect_async(url).await.unwrap();
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "resources/subscribe",
+            "params": { "uri": "file:///log" }
+        });
+        socket
+            .send(tungstenite::Message::Text(request.to_string()))
+            .await
+            .unwrap();
+
+        // Notifications are not answered
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        socket
+            .send(tungstenite::Message::Text(notification.to_string()))
+            .await
+            .unwrap();
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": { "name": "calculator_add", "arguments": { "a": 2, "b": 3 } }
+        });
+        socket
+            .send(tungstenite::Message::Text(request.to_string()))
+            .await
+            .unwrap();
+
+        let mut responses = Vec::new();
+        while responses.len() < 2 {
+            let message = socket.next().await.unwrap().unwrap();
+            let message: Value = serde_json::from_str(message.to_text().unwrap()).unwrap();
+            responses.push(message);
+        }
+        responses.sort_by_key(|r| r["id"].as_u64());
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[1]["result"]["content"][0]["text"], "5");
+
+        // Server-initiated messages share the socket
+        server.notify_resource_updated("file:///log").await;
+        let message = socket.next().await.unwrap().unwrap();
+        let message: Value = serde_json::from_str(message.to_text().unwrap()).unwrap();
+        assert_eq!(message["method"], "notifications/resources/updated");
+        assert_eq!(message["params"]["uri"], "file:///log");
+    }
+
+    #[tokio::test]
+    async fn test_queued_request_cancelled() {
+        let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+        server.register_tools(Calculator::default()).await;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/ws", listener.local_addr().unwrap());
+        let app = WebSocketTransport::new()
+            .with_max_in_flight(1)
+            .into_router(server);
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        let (mut socket, _) = connect_async(url).await.unwrap();
+
+        // Occupy the only slot, then queue a call and cancel it while it waits
+        for (id, ms) in [(1, 200), (2, 0)] {
+            let request = json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": "tools/call",
+                "params": { "name": "calculator_wait", "arguments": { "ms": ms } }
+            });
+            send(&mut socket, request).await;
+        }
+        let cancel = json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/cancelled",
+            "params": { "requestId": 2 }
+        });
+        send(&mut socket, cancel).await;
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "tools/call",
+            "params": { "name": "calculator_add", "arguments": { "a": 2, "b": 3 } }
+        });
+        send(&mut socket, request).await;
+
+        let mut ids = Vec::new();
+        while ids.len() < 2 {
+            let message = socket.next().await.unwrap().unwrap();
+            let message: Value = serde_json::from_str(message.to_text().unwrap()).unwrap();
+            ids.push(message["id"].clone());
+        }
+        assert_eq!(ids, vec![json!(1), json!(3)]);
+    }
+}
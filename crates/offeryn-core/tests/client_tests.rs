@@ -0,0 +1,298 @@
+use async_trait::async_trait;
+use jsonrpc_core::Request as JsonRpcRequest;
+use offeryn_core::transport::SseTransport;
+use offeryn_core::{
+    ClientError, ClientTransport, McpClient, McpServer, Session, SseClientTransport,
+};
+use offeryn_derive::tool;
+use offeryn_types::*;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+
+/// Feeds client messages straight into an in-process server.
+struct InProcessTransport {
+    server: Arc<McpServer>,
+    session: Arc<Session>,
+    outgoing: mpsc::Sender<Value>,
+    incoming: Mutex<mpsc::Receiver<Value>>,
+}
+
+impl InProcessTransport {
+    fn new(server: Arc<McpServer>) -> Self {
+        let (notify_tx, _notify_rx) = mpsc::channel(10);
+        let session = server.connect(notify_tx);
+        let (outgoing, incoming) = mpsc::channel(10);
+        Self {
+            server,
+            session,
+            outgoing,
+            incoming: Mutex::new(incoming),
+        }
+    }
+}
+
+#[async_trait]
+impl ClientTransport for InProcessTransport {
+    async fn send(&self, message: Value) -> Result<(), ClientError> {
+        let request: JsonRpcRequest = serde_json::from_value(message)?;
+        let response = self
+            .server
+            .handle_session_request(&self.session, request)
+            .await
+            .map_err(|e| ClientError::Transport(e.to_string()))?;
+        let Some(response) = response else {
+            return Ok(());
+        };
+        self.outgoing
+            .send(serde_json::to_value(response)?)
+            .await
+            .map_err(|_| ClientError::ConnectionClosed)
+    }
+
+    async fn receive(&self) -> Result<Option<Value>, ClientError> {
+        Ok(self.incoming.lock().await.recv().await)
+    }
+}
+
+/// Lets a test play the server by hand.
+struct ScriptedTransport {
+    sent: mpsc::Sender<Value>,
+    incoming: Mutex<mpsc::Receiver<Value>>,
+}
+
+#[async_trait]
+impl ClientTransport for ScriptedTransport {
+    async fn send(&self, message: Value) -> Result<(), ClientError> {
+        self.sent
+            .send(message)
+            .await
+            .map_err(|_| ClientError::ConnectionClosed)
+    }
+
+    async fn receive(&self) -> Result<Option<Value>, ClientError> {
+        Ok(self.incoming.lock().await.recv().await)
+    }
+}
+
+fn scripted() -> (McpClient, mpsc::Sender<Value>, mpsc::Receiver<Value>) {
+    let (server_tx, incoming) = mpsc::channel(10);
+    let (sent, server_rx) = mpsc::channel(10);
+    let transport = ScriptedTransport {
+        sent,
+        incoming: Mutex::new(incoming),
+    };
+    (McpClient::new(transport), server_tx, server_rx)
+}
+
+#[derive(Default)]
+struct Calculator {}
+
+#[tool]
+impl Calculator {
+    /// Add two numbers
+    async fn add(&self, a: i64, b: i64) -> i64 {
+        a + b
+    }
+
+    /// Divide two numbers
+    async fn divide(&self, a: i64, b: i64) -> Result<f64, String> {
+        if b == 0 {
+            Err("Cannot divide by zero".to_string())
+        } else {
+            Ok(a as f64 / b as f64)
+        }
+    }
+}
+
+#[derive(Default)]
+struct Greeter {}
+
+#[tool]
+impl Greeter {
+    /// Greet the connected client
+    async fn hello(&self, ctx: ToolContext) -> String {
+        match ctx.client_info() {
+            Some(client) => format!("Hello, {} {}", client.name, client.version),
+            None => "Hello, stranger".to_string(),
+        }
+    }
+}
+
+async fn connect() -> McpClient {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    server.register_tools(Calculator::default()).await;
+    McpClient::new(InProcessTransport::new(server))
+}
+
+#[tokio::test]
+async fn test_client_initialize() {
+    let client = connect().await;
+    let result = client
+        .initialize(Implementation {
+            name: "test-client".to_string(),
+            version: "0.1.0".to_string(),
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(result.server_info.name, "test-server");
+    assert_eq!(result.protocol_version, LATEST_PROTOCOL_VERSION);
+}
+
+#[tokio::test]
+async fn test_tool_context_client_info() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    server.register_tools(Greeter::default()).await;
+    let client = McpClient::new(InProcessTransport::new(server));
+
+    let tools = client.list_tools().await.unwrap().tools;
+    assert_eq!(tools[0].input_schema["properties"], json!({}));
+
+    let result = client.call_tool("greeter_hello", json!({})).await.unwrap();
+    match &result.content[0] {
+        Content::Text { text, .. } => assert_eq!(text, "Hello, stranger"),
+        _ => panic!("Expected text content"),
+    }
+
+    client
+        .initialize(Implementation {
+            name: "test-client".to_string(),
+            version: "0.1.0".to_string(),
+        })
+        .await
+        .unwrap();
+    let result = client.call_tool("greeter_hello", json!({})).await.unwrap();
+    match &result.content[0] {
+        Content::Text { text, .. } => assert_eq!(text, "Hello, test-client 0.1.0"),
+        _ => panic!("Expected text content"),
+    }
+}
+
+#[tokio::test]
+async fn test_client_list_and_call_tools() {
+    let client = connect().await;
+
+    let mut tools = client.list_tools().await.unwrap().tools;
+    tools.sort_by(|a, b| a.name.cmp(&b.name));
+    assert_eq!(tools.len(), 2);
+    assert_eq!(tools[0].name, "calculator_add");
+    assert_eq!(tools[0].description, "Add two numbers");
+
+    let result = client
+        .call_tool("calculator_add", json!({ "a": 2, "b": 3 }))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+    match &result.content[0] {
+        Content::Text { text, .. } => assert_eq!(text, "5"),
+        _ => panic!("Expected text content"),
+    }
+
+    let result = client
+        .call_tool("calculator_divide", json!({ "a": 1, "b": 0 }))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+}
+
+#[tokio::test]
+async fn test_client_rpc_error() {
+    let client = connect().await;
+
+    let result: Result<Value, _> = client.request("unknown/method", Value::Null).await;
+    match result {
+        Err(ClientError::Rpc(error)) => {
+            assert_eq!(error.code, jsonrpc_core::ErrorCode::MethodNotFound)
+        }
+        _ => panic!("Expected RPC error"),
+    }
+
+    assert!(matches!(
+        client.call_tool("calculator_add", json!([1, 2])).await,
+        Err(ClientError::InvalidRequest(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_client_answers_ping() {
+    let (_client, server_tx, mut server_rx) = scripted();
+
+    server_tx
+        .send(json!({ "jsonrpc": "2.0", "id": "p1", "method": "ping" }))
+        .await
+        .unwrap();
+    assert_eq!(
+        server_rx.recv().await.unwrap(),
+        json!({ "jsonrpc": "2.0", "result": {}, "id": "p1" })
+    );
+
+    // Other server requests are still refused
+    server_tx
+        .send(json!({ "jsonrpc": "2.0", "id": "p2", "method": "roots/list" }))
+        .await
+        .unwrap();
+    let reply = server_rx.recv().await.unwrap();
+    assert_eq!(reply["id"], "p2");
+    assert_eq!(reply["error"]["code"], -32601);
+}
+
+#[tokio::test]
+async fn test_request_after_close_fails() {
+    let (client, server_tx, _server_rx) = scripted();
+    drop(server_tx);
+
+    // Whether or not the reader has noticed yet, the request must not hang
+    let result = tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        client.request::<Value>("tools/list", Value::Null),
+    )
+    .await
+    .expect("request hung after the connection closed");
+    assert!(matches!(result, Err(ClientError::ConnectionClosed)));
+}
+
+#[tokio::test]
+async fn test_sse_client_transport() {
+    let server = Arc::new(McpServer::new("sse-server", "1.0.0"));
+    server.register_tools(Calculator::default()).await;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = SseTransport::create_router(server);
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let transport = SseClientTransport::connect(&format!("http://{}/sse", addr))
+        .await
+        .unwrap();
+    let client = McpClient::new(transport);
+
+    let result = client
+        .initialize(Implementation {
+            name: "sse-client".to_string(),
+            version: "0.1.0".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(result.server_info.name, "sse-server");
+
+    // Concurrent requests are correlated by id
+    let (sum, quotient) = tokio::join!(
+        client.call_tool("calculator_add", json!({ "a": 2, "b": 3 })),
+        client.call_tool("calculator_divide", json!({ "a": 9, "b": 3 })),
+    );
+    match &sum.unwrap().content[0] {
+        Content::Text { text, .. } => assert_eq!(text, "5"),
+        _ => panic!("Expected text content"),
+    }
+    match &quotient.unwrap().content[0] {
+        Content::Text { text, .. } => assert_eq!(text, "3"),
+        _ => panic!("Expected text content"),
+    }
+
+    // Error responses are delivered over the event stream too
+    let result: Result<Value, _> = client.request("unknown/method", Value::Null).await;
+    assert!(matches!(result, Err(ClientError::Rpc(_))));
+}
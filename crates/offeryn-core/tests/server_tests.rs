@@ -1,10 +1,14 @@
 use async_trait::async_trait;
-use jsonrpc_core::{Call, Id, MethodCall, Output, Params, Version};
-use offeryn_core::{McpError, McpServer};
+use jsonrpc_core::{Call, Id, MethodCall, Notification, Output, Params, Version};
+use offeryn_core::{McpError, McpServer, Session};
 use offeryn_types::McpTool;
 use offeryn_types::*;
 use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
 
 // Mock tool for testing
 struct MockTool;
@@ -30,18 +34,381 @@ impl McpTool for MockTool {
         })
     }
 
-    async fn execute(&self, args: Value) -> Result<ToolResult, String> {
+    async fn execute(&self, args: Value, _ctx: ToolContext) -> Result<CallToolResult, String> {
         let echo = args["echo"].as_str().ok_or("Missing echo parameter")?;
-        Ok(ToolResult {
-            content: vec![ToolContent {
-                text: echo.to_string(),
-                r#type: "text".to_string(),
+        Ok(CallToolResult::text(echo.to_string()))
+    }
+}
+
+// Mock tool that takes a while to finish
+struct SlowTool;
+
+#[async_trait]
+impl McpTool for SlowTool {
+    fn name(&self) -> &str {
+        "slow_tool"
+    }
+
+    fn description(&self) -> &str {
+        "A tool that sleeps before answering"
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn execute(&self, _args: Value, _ctx: ToolContext) -> Result<CallToolResult, String> {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        Ok(CallToolResult::text("done"))
+    }
+}
+
+// Mock tool that waits until it is cancelled
+struct BlockingTool {
+    cancelled: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl McpTool for BlockingTool {
+    fn name(&self) -> &str {
+        "blocking_tool"
+    }
+
+    fn description(&self) -> &str {
+        "A tool that never finishes on its own"
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn execute(&self, _args: Value, ctx: ToolContext) -> Result<CallToolResult, String> {
+        let token = ctx.cancellation_token().clone();
+        let cancelled = self.cancelled.clone();
+        // Observe the token from outside the request future, which is dropped
+        tokio::spawn(async move {
+            token.cancelled().await;
+            cancelled.store(true, Ordering::SeqCst);
+        });
+        std::future::pending().await
+    }
+}
+
+// Mock tool that reports progress while it works
+struct ProgressTool;
+
+#[async_trait]
+impl McpTool for ProgressTool {
+    fn name(&self) -> &str {
+        "progress_tool"
+    }
+
+    fn description(&self) -> &str {
+        "A tool that reports progress"
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn execute(&self, _args: Value, ctx: ToolContext) -> Result<CallToolResult, String> {
+        for step in 1..=2 {
+            ctx.progress(step as f64, Some(2.0), Some(format!("Step {}", step)))
+                .await?;
+        }
+        Ok(CallToolResult::text("done"))
+    }
+}
+
+// Mock tool that asks the client to sample a completion
+struct SamplingTool;
+
+#[async_trait]
+impl McpTool for SamplingTool {
+    fn name(&self) -> &str {
+        "sampling_tool"
+    }
+
+    fn description(&self) -> &str {
+        "A tool that requests sampling from the client"
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn execute(&self, _args: Value, ctx: ToolContext) -> Result<CallToolResult, String> {
+        let request = CreateMessageRequest {
+            messages: vec![SamplingMessage {
+                role: Role::User,
+                content: Content::text("Hello"),
             }],
-            is_error: false,
+            max_tokens: 10,
+            ..Default::default()
+        };
+        let (text, is_error) = match ctx.create_message(request).await {
+            Ok(result) => match result.content {
+                Content::Text { text, .. } => (text, false),
+                _ => ("unexpected content".to_string(), true),
+            },
+            Err(e) => (e, true),
+        };
+        Ok(CallToolResult {
+            content: vec![Content::text(text)],
+            structured_content: None,
+            is_error: Some(is_error),
         })
     }
 }
 
+// Mock tool that returns an image
+struct ChartTool;
+
+#[async_trait]
+impl McpTool for ChartTool {
+    fn name(&self) -> &str {
+        "chart_tool"
+    }
+
+    fn description(&self) -> &str {
+        "A tool that renders a chart"
+    }
+
+    fn title(&self) -> Option<&str> {
+        Some("Render chart")
+    }
+
+    fn annotations(&self) -> Option<ToolAnnotations> {
+        Some(ToolAnnotations {
+            read_only_hint: Some(true),
+            open_world_hint: Some(false),
+            ..Default::default()
+        })
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn execute(&self, _args: Value, _ctx: ToolContext) -> Result<CallToolResult, String> {
+        Ok(CallToolResult {
+            content: vec![Content::image("iVBORw0KGgo=", "image/png")],
+            structured_content: None,
+            is_error: None,
+        })
+    }
+}
+
+// Mock tool that uses content added in newer protocol revisions
+struct MediaTool;
+
+#[async_trait]
+impl McpTool for MediaTool {
+    fn name(&self) -> &str {
+        "media_tool"
+    }
+
+    fn description(&self) -> &str {
+        "A tool that returns a recording"
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({ "type": "object" }))
+    }
+
+    fn title(&self) -> Option<&str> {
+        Some("Fetch recording")
+    }
+
+    fn annotations(&self) -> Option<ToolAnnotations> {
+        Some(ToolAnnotations {
+            read_only_hint: Some(true),
+            ..Default::default()
+        })
+    }
+
+    async fn execute(&self, _args: Value, _ctx: ToolContext) -> Result<CallToolResult, String> {
+        Ok(CallToolResult {
+            content: vec![
+                Content::audio("UklGRg==", "audio/wav"),
+                Content::resource_link("file:///take1.wav", "take1.wav"),
+            ],
+            structured_content: Some(json!({ "seconds": 3 })),
+            is_error: None,
+        })
+    }
+}
+
+// Mock tool with a title but no annotations
+struct TitledTool;
+
+#[async_trait]
+impl McpTool for TitledTool {
+    fn name(&self) -> &str {
+        "titled_tool"
+    }
+
+    fn description(&self) -> &str {
+        "A tool with only a title"
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    fn title(&self) -> Option<&str> {
+        Some("Titled tool")
+    }
+
+    async fn execute(&self, _args: Value, _ctx: ToolContext) -> Result<CallToolResult, String> {
+        Ok(CallToolResult::text("done"))
+    }
+}
+
+// Mock resource for testing
+struct MockResource;
+
+#[async_trait]
+impl McpResource for MockResource {
+    fn uri(&self) -> &str {
+        "config://app"
+    }
+
+    fn name(&self) -> &str {
+        "App config"
+    }
+
+    fn mime_type(&self) -> Option<&str> {
+        Some("application/json")
+    }
+
+    async fn read(&self) -> Result<Vec<ResourceContents>, String> {
+        Ok(vec![ResourceContents::Text(TextResourceContents {
+            uri: self.uri().to_string(),
+            mime_type: Some("application/json".to_string()),
+            text: r#"{"debug":true}"#.to_string(),
+        })])
+    }
+}
+
+// Mock resource template for testing
+struct MockFileTemplate;
+
+#[async_trait]
+impl McpResourceTemplate for MockFileTemplate {
+    fn uri_template(&self) -> &str {
+        "file:///{path}"
+    }
+
+    fn name(&self) -> &str {
+        "Files"
+    }
+
+    fn matches(&self, uri: &str) -> bool {
+        uri.starts_with("file:///")
+    }
+
+    async fn read(&self, uri: &str) -> Result<Vec<ResourceContents>, String> {
+        let path = uri.trim_start_matches("file:///");
+        if path == "missing" {
+            return Err("No such file".to_string());
+        }
+        Ok(vec![ResourceContents::Text(TextResourceContents {
+            uri: uri.to_string(),
+            mime_type: None,
+            text: format!("contents of {}", path),
+        })])
+    }
+}
+
+// Mock prompt for testing
+struct MockPrompt;
+
+#[async_trait]
+impl McpPrompt for MockPrompt {
+    fn name(&self) -> &str {
+        "code_review"
+    }
+
+    fn description(&self) -> &str {
+        "Review a piece of code"
+    }
+
+    fn arguments(&self) -> Vec<PromptArgument> {
+        vec![
+            PromptArgument {
+                name: "code".to_string(),
+                description: Some("The code to review".to_string()),
+                required: Some(true),
+            },
+            PromptArgument {
+                name: "language".to_string(),
+                description: None,
+                required: Some(false),
+            },
+        ]
+    }
+
+    async fn render(&self, args: HashMap<String, String>) -> Result<Vec<PromptMessage>, String> {
+        let language = args.get("language").map_or("unknown", String::as_str);
+        Ok(vec![PromptMessage {
+            role: Role::User,
+            content: Content::text(format!("Review this {} code:\n{}", language, args["code"])),
+        }])
+    }
+}
+
+// Mock prompt that uses content added in newer protocol revisions
+struct MediaPrompt;
+
+#[async_trait]
+impl McpPrompt for MediaPrompt {
+    fn name(&self) -> &str {
+        "transcribe"
+    }
+
+    fn description(&self) -> &str {
+        "Transcribe a recording"
+    }
+
+    fn arguments(&self) -> Vec<PromptArgument> {
+        Vec::new()
+    }
+
+    async fn render(&self, _args: HashMap<String, String>) -> Result<Vec<PromptMessage>, String> {
+        Ok(vec![
+            PromptMessage {
+                role: Role::User,
+                content: Content::text("Transcribe this recording"),
+            },
+            PromptMessage {
+                role: Role::User,
+                content: Content::audio("UklGRg==", "audio/wav"),
+            },
+            PromptMessage {
+                role: Role::User,
+                content: Content::resource_link("file:///take1.wav", "take1.wav"),
+            },
+        ])
+    }
+}
+
+fn method_call(method: &str, params: Value) -> JsonRpcRequest {
+    JsonRpcRequest::Single(Call::MethodCall(MethodCall {
+        jsonrpc: Some(Version::V2),
+        id: Id::Num(1),
+        method: method.to_string(),
+        params: match params {
+            Value::Object(map) => Params::Map(map),
+            _ => Params::None,
+        },
+    }))
+}
+
 #[tokio::test]
 async fn test_tools_list() {
     let server = Arc::new(McpServer::new("test-server", "1.0.0"));
@@ -54,7 +421,7 @@ async fn test_tools_list() {
         params: Params::None,
     }));
 
-    let response = server.handle_request(request).await.unwrap();
+    let response = server.handle_request(request).await.unwrap().unwrap();
 
     match response {
         JsonRpcResponse::Single(Output::Success(success)) => {
@@ -90,16 +457,15 @@ async fn test_tool_execution() {
         params: Params::Map(params),
     }));
 
-    let response = server.handle_request(request).await.unwrap();
+    let response = server.handle_request(request).await.unwrap().unwrap();
 
     match response {
         JsonRpcResponse::Single(Output::Success(success)) => {
             let result: CallToolResult = serde_json::from_value(success.result).unwrap();
             assert_eq!(result.content.len(), 1);
             match &result.content[0] {
-                Content::Text { text } => assert_eq!(text, "Hello, World!"),
-                Content::Image { .. } => panic!("Expected text content"),
-                Content::EmbeddedResource { .. } => panic!("Expected text content"),
+                Content::Text { text, .. } => assert_eq!(text, "Hello, World!"),
+                _ => panic!("Expected text content"),
             }
             assert_eq!(result.is_error, Some(false));
         }
@@ -138,7 +504,7 @@ async fn test_invalid_method() {
         params: Params::None,
     }));
 
-    let response = server.handle_request(request).await.unwrap();
+    let response = server.handle_request(request).await.unwrap().unwrap();
 
     match response {
         JsonRpcResponse::Single(Output::Failure(failure)) => {
@@ -164,7 +530,7 @@ async fn test_initialize() {
         params: Params::None,
     }));
 
-    let response = server.handle_request(request).await.unwrap();
+    let response = server.handle_request(request).await.unwrap().unwrap();
 
     match response {
         JsonRpcResponse::Single(Output::Success(success)) => {
@@ -178,3 +544,993 @@ async fn test_initialize() {
         _ => panic!("Expected successful response"),
     }
 }
+
+#[tokio::test]
+async fn test_resources_list() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    server.register_resource(MockResource).await;
+
+    let response = server
+        .handle_request(method_call("resources/list", Value::Null))
+        .await
+        .unwrap()
+        .unwrap();
+
+    match response {
+        JsonRpcResponse::Single(Output::Success(success)) => {
+            let result: ListResourcesResult = serde_json::from_value(success.result).unwrap();
+            assert_eq!(result.resources.len(), 1);
+            assert_eq!(result.resources[0].uri, "config://app");
+            assert_eq!(result.resources[0].name, "App config");
+            assert_eq!(
+                result.resources[0].mime_type.as_deref(),
+                Some("application/json")
+            );
+        }
+        _ => panic!("Expected successful response"),
+    }
+}
+
+#[tokio::test]
+async fn test_resource_templates_list() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    server.register_resource_template(MockFileTemplate).await;
+
+    let response = server
+        .handle_request(method_call("resources/templates/list", Value::Null))
+        .await
+        .unwrap()
+        .unwrap();
+
+    match response {
+        JsonRpcResponse::Single(Output::Success(success)) => {
+            assert_eq!(
+                success.result["resourceTemplates"][0]["uriTemplate"],
+                "file:///{path}"
+            );
+            let result: ListResourceTemplatesResult =
+                serde_json::from_value(success.result).unwrap();
+            assert_eq!(result.resource_templates.len(), 1);
+            assert_eq!(result.resource_templates[0].name, "Files");
+        }
+        _ => panic!("Expected successful response"),
+    }
+}
+
+#[tokio::test]
+async fn test_resource_read() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    server.register_resource(MockResource).await;
+    server.register_resource_template(MockFileTemplate).await;
+
+    let response = server
+        .handle_request(method_call(
+            "resources/read",
+            json!({ "uri": "config://app" }),
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    match response {
+        JsonRpcResponse::Single(Output::Success(success)) => {
+            let result: ReadResourceResult = serde_json::from_value(success.result).unwrap();
+            match &result.contents[0] {
+                ResourceContents::Text(contents) => {
+                    assert_eq!(contents.uri, "config://app");
+                    assert_eq!(contents.text, r#"{"debug":true}"#);
+                }
+                ResourceContents::Blob(_) => panic!("Expected text contents"),
+            }
+        }
+        _ => panic!("Expected successful response"),
+    }
+
+    let response = server
+        .handle_request(method_call(
+            "resources/read",
+            json!({ "uri": "file:///notes.txt" }),
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    match response {
+        JsonRpcResponse::Single(Output::Success(success)) => {
+            assert_eq!(
+                success.result["contents"][0]["text"],
+                "contents of notes.txt"
+            );
+        }
+        _ => panic!("Expected successful response"),
+    }
+
+    let response = server
+        .handle_request(method_call(
+            "resources/read",
+            json!({ "uri": "file:///missing" }),
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    match response {
+        JsonRpcResponse::Single(Output::Failure(failure)) => {
+            assert_eq!(failure.error.message, "No such file");
+        }
+        _ => panic!("Expected failure response"),
+    }
+}
+
+#[tokio::test]
+async fn test_unknown_resource() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    server.register_resource(MockResource).await;
+
+    let response = server
+        .handle_request(method_call(
+            "resources/read",
+            json!({ "uri": "config://other" }),
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+
+    match response {
+        JsonRpcResponse::Single(Output::Failure(failure)) => {
+            assert_eq!(failure.error.code.code(), -32002);
+        }
+        _ => panic!("Expected failure response"),
+    }
+}
+
+#[tokio::test]
+async fn test_initialize_advertises_resources() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+
+    let response = server
+        .handle_request(method_call("initialize", Value::Null))
+        .await
+        .unwrap()
+        .unwrap();
+    match response {
+        JsonRpcResponse::Single(Output::Success(success)) => {
+            let result: InitializeResult = serde_json::from_value(success.result).unwrap();
+            assert!(result.capabilities.resources.is_none());
+        }
+        _ => panic!("Expected successful response"),
+    }
+
+    server.register_resource(MockResource).await;
+    let response = server
+        .handle_request(method_call("initialize", Value::Null))
+        .await
+        .unwrap()
+        .unwrap();
+    match response {
+        JsonRpcResponse::Single(Output::Success(success)) => {
+            let result: InitializeResult = serde_json::from_value(success.result).unwrap();
+            assert!(result.capabilities.resources.is_some());
+        }
+        _ => panic!("Expected successful response"),
+    }
+}
+
+#[tokio::test]
+async fn test_resource_subscriptions() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    server.register_resource(MockResource).await;
+
+    let (tx, mut rx) = mpsc::channel(10);
+    let session = server.connect(tx);
+    let (other_tx, mut other_rx) = mpsc::channel(10);
+    let _other = server.connect(other_tx);
+
+    let response = server
+        .handle_session_request(
+            &session,
+            method_call("resources/subscribe", json!({ "uri": "config://app" })),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(
+        response,
+        JsonRpcResponse::Single(Output::Success(_))
+    ));
+    assert!(session.is_subscribed("config://app"));
+
+    server.notify_resource_updated("config://app").await;
+    match rx.try_recv().unwrap() {
+        Call::Notification(notification) => {
+            assert_eq!(notification.method, "notifications/resources/updated");
+            let params: ResourceUpdatedNotification = notification.params.parse().unwrap();
+            assert_eq!(params.uri, "config://app");
+        }
+        _ => panic!("Expected notification"),
+    }
+    assert!(other_rx.try_recv().is_err());
+
+    server
+        .handle_session_request(
+            &session,
+            method_call("resources/unsubscribe", json!({ "uri": "config://app" })),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    server.notify_resource_updated("config://app").await;
+    assert!(rx.try_recv().is_err());
+
+    server.disconnect(session.id());
+    assert!(server.session(session.id()).is_none());
+}
+
+#[tokio::test]
+async fn test_prompts_list() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    server.register_prompt(MockPrompt).await;
+
+    let response = server
+        .handle_request(method_call("prompts/list", Value::Null))
+        .await
+        .unwrap()
+        .unwrap();
+
+    match response {
+        JsonRpcResponse::Single(Output::Success(success)) => {
+            let result: ListPromptsResult = serde_json::from_value(success.result).unwrap();
+            assert_eq!(result.prompts.len(), 1);
+            assert_eq!(result.prompts[0].name, "code_review");
+            assert_eq!(
+                result.prompts[0].description.as_deref(),
+                Some("Review a piece of code")
+            );
+            assert_eq!(result.prompts[0].arguments.len(), 2);
+            assert_eq!(result.prompts[0].arguments[0].required, Some(true));
+        }
+        _ => panic!("Expected successful response"),
+    }
+}
+
+#[tokio::test]
+async fn test_prompt_get() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    server.register_prompt(MockPrompt).await;
+
+    let response = server
+        .handle_request(method_call(
+            "prompts/get",
+            json!({
+                "name": "code_review",
+                "arguments": { "code": "fn main() {}", "language": "rust" }
+            }),
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+
+    match response {
+        JsonRpcResponse::Single(Output::Success(success)) => {
+            let result: GetPromptResult = serde_json::from_value(success.result).unwrap();
+            assert_eq!(result.messages.len(), 1);
+            assert_eq!(result.messages[0].role, Role::User);
+            match &result.messages[0].content {
+                Content::Text { text, .. } => {
+                    assert_eq!(text, "Review this rust code:\nfn main() {}")
+                }
+                _ => panic!("Expected text content"),
+            }
+        }
+        _ => panic!("Expected successful response"),
+    }
+}
+
+#[tokio::test]
+async fn test_prompt_get_errors() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    server.register_prompt(MockPrompt).await;
+
+    let response = server
+        .handle_request(method_call("prompts/get", json!({ "name": "unknown" })))
+        .await
+        .unwrap()
+        .unwrap();
+    match response {
+        JsonRpcResponse::Single(Output::Failure(failure)) => {
+            assert_eq!(
+                failure.error.code.code(),
+                jsonrpc_core::ErrorCode::InvalidParams.code()
+            );
+        }
+        _ => panic!("Expected failure response"),
+    }
+
+    let response = server
+        .handle_request(method_call(
+            "prompts/get",
+            json!({ "name": "code_review", "arguments": { "language": "rust" } }),
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    match response {
+        JsonRpcResponse::Single(Output::Failure(failure)) => {
+            assert_eq!(failure.error.message, "Missing required argument: code");
+        }
+        _ => panic!("Expected failure response"),
+    }
+}
+
+#[tokio::test]
+async fn test_concurrent_tool_calls() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    server.register_tool(SlowTool).await;
+
+    let start = Instant::now();
+    let calls: Vec<_> = (0..4)
+        .map(|_| {
+            let server = server.clone();
+            tokio::spawn(async move {
+                server
+                    .handle_request(method_call(
+                        "tools/call",
+                        json!({ "name": "slow_tool", "arguments": {} }),
+                    ))
+                    .await
+            })
+        })
+        .collect();
+
+    // Listing tools must not wait for the running calls
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let list_start = Instant::now();
+    server
+        .handle_request(method_call("tools/list", json!({})))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(list_start.elapsed() < Duration::from_millis(100));
+
+    for call in calls {
+        let response = call.await.unwrap().unwrap().unwrap();
+        assert!(matches!(
+            response,
+            JsonRpcResponse::Single(Output::Success(_))
+        ));
+    }
+    // Serialized execution would take at least 800ms
+    assert!(start.elapsed() < Duration::from_millis(600));
+}
+
+#[tokio::test]
+async fn test_notifications_have_no_response() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+
+    for method in ["notifications/initialized", "notifications/unknown"] {
+        let request = JsonRpcRequest::Single(Call::Notification(Notification {
+            jsonrpc: Some(Version::V2),
+            method: method.to_string(),
+            params: Params::None,
+        }));
+        let response = server.handle_request(request).await.unwrap();
+        assert!(response.is_none(), "{} should not be answered", method);
+    }
+}
+
+#[tokio::test]
+async fn test_batch_request() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    server.register_tool(MockTool).await;
+    server.register_prompt(MockPrompt).await;
+
+    let request: JsonRpcRequest = serde_json::from_value(json!([
+        { "jsonrpc": "2.0", "id": 1, "method": "tools/list" },
+        { "jsonrpc": "2.0", "method": "notifications/initialized" },
+        { "jsonrpc": "2.0", "id": 2, "method": "prompts/list" },
+        { "jsonrpc": "2.0", "id": 3, "method": "tools/call", "params": { "name": "missing" } }
+    ]))
+    .unwrap();
+
+    let response = server.handle_request(request).await.unwrap().unwrap();
+    let JsonRpcResponse::Batch(outputs) = response else {
+        panic!("Expected batch response");
+    };
+    assert_eq!(outputs.len(), 3);
+    assert!(matches!(&outputs[0], Output::Success(s) if s.id == Id::Num(1)));
+    assert!(matches!(&outputs[1], Output::Success(s) if s.id == Id::Num(2)));
+    match &outputs[2] {
+        Output::Failure(failure) => {
+            assert_eq!(failure.id, Id::Num(3));
+            assert_eq!(failure.error.code, jsonrpc_core::ErrorCode::MethodNotFound);
+        }
+        _ => panic!("Expected failure for unknown tool"),
+    }
+}
+
+#[tokio::test]
+async fn test_batch_edge_cases() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+
+    // A batch of notifications gets no response at all
+    let request: JsonRpcRequest = serde_json::from_value(json!([
+        { "jsonrpc": "2.0", "method": "notifications/initialized" },
+        { "jsonrpc": "2.0", "method": "notifications/cancelled" }
+    ]))
+    .unwrap();
+    assert!(server.handle_request(request).await.unwrap().is_none());
+
+    // An empty batch is an invalid request
+    let response = server
+        .handle_request(JsonRpcRequest::Batch(vec![]))
+        .await
+        .unwrap()
+        .unwrap();
+    match response {
+        JsonRpcResponse::Single(Output::Failure(failure)) => {
+            assert_eq!(failure.id, Id::Null);
+            assert_eq!(failure.error.code, jsonrpc_core::ErrorCode::InvalidRequest);
+        }
+        _ => panic!("Expected failure response"),
+    }
+}
+
+#[tokio::test]
+async fn test_cancel_tool_call() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    let cancelled = Arc::new(AtomicBool::new(false));
+    server
+        .register_tool(BlockingTool {
+            cancelled: cancelled.clone(),
+        })
+        .await;
+    let (tx, _rx) = mpsc::channel(10);
+    let session = server.connect(tx);
+
+    let call = tokio::spawn({
+        let server = server.clone();
+        let session = session.clone();
+        async move {
+            server
+                .handle_session_request(
+                    &session,
+                    method_call("tools/call", json!({ "name": "blocking_tool" })),
+                )
+                .await
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let cancel = JsonRpcRequest::Single(Call::Notification(Notification {
+        jsonrpc: Some(Version::V2),
+        method: "notifications/cancelled".to_string(),
+        params: Params::Map(
+            json!({ "requestId": 1, "reason": "User requested cancellation" })
+                .as_object()
+                .unwrap()
+                .clone(),
+        ),
+    }));
+    assert!(server
+        .handle_session_request(&session, cancel)
+        .await
+        .unwrap()
+        .is_none());
+
+    // Cancelled requests are not answered
+    let response = tokio::time::timeout(Duration::from_secs(1), call)
+        .await
+        .expect("cancelled call should finish")
+        .unwrap()
+        .unwrap();
+    assert!(response.is_none());
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert!(cancelled.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn test_disconnect_cancels_requests() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    let cancelled = Arc::new(AtomicBool::new(false));
+    server
+        .register_tool(BlockingTool {
+            cancelled: cancelled.clone(),
+        })
+        .await;
+    let (tx, _rx) = mpsc::channel(10);
+    let session = server.connect(tx);
+
+    let call = tokio::spawn({
+        let server = server.clone();
+        let session = session.clone();
+        async move {
+            server
+                .handle_session_request(
+                    &session,
+                    method_call("tools/call", json!({ "name": "blocking_tool" })),
+                )
+                .await
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    server.disconnect(session.id());
+    let response = tokio::time::timeout(Duration::from_secs(1), call)
+        .await
+        .expect("call should finish after disconnect")
+        .unwrap()
+        .unwrap();
+    assert!(response.is_none());
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert!(cancelled.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn test_progress_notifications() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    server.register_tool(ProgressTool).await;
+    let (tx, mut rx) = mpsc::channel(10);
+    let session = server.connect(tx);
+
+    let response = server
+        .handle_session_request(
+            &session,
+            method_call(
+                "tools/call",
+                json!({ "name": "progress_tool", "_meta": { "progressToken": "index-1" } }),
+            ),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(
+        response,
+        JsonRpcResponse::Single(Output::Success(_))
+    ));
+
+    for step in 1..=2 {
+        let Some(Call::Notification(notification)) = rx.recv().await else {
+            panic!("Expected progress notification");
+        };
+        assert_eq!(notification.method, "notifications/progress");
+        let params: ProgressNotification = notification.params.parse().unwrap();
+        assert_eq!(
+            params.progress_token,
+            ProgressToken::String("index-1".to_string())
+        );
+        assert_eq!(params.progress, step as f64);
+        assert_eq!(params.total, Some(2.0));
+        assert_eq!(params.message, Some(format!("Step {}", step)));
+    }
+
+    // Without a progress token the tool's updates are dropped
+    server
+        .handle_session_request(
+            &session,
+            method_call("tools/call", json!({ "name": "progress_tool" })),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(rx.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_sampling_requires_client_capability() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    server.register_tool(SamplingTool).await;
+    let (tx, mut rx) = mpsc::channel(10);
+    let session = server.connect(tx);
+
+    let response = server
+        .handle_session_request(
+            &session,
+            method_call("tools/call", json!({ "name": "sampling_tool" })),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    let JsonRpcResponse::Single(Output::Success(success)) = response else {
+        panic!("Expected successful response");
+    };
+    assert_eq!(success.result["isError"], true);
+    assert_eq!(
+        success.result["content"][0]["text"],
+        "Client does not support sampling"
+    );
+    // Nothing is sent to a client that cannot sample
+    assert!(rx.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_roots_list() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    let (tx, mut rx) = mpsc::channel(10);
+    let session = server.connect(tx);
+
+    server
+        .handle_session_request(
+            &session,
+            method_call(
+                "initialize",
+                json!({
+                    "protocolVersion": LATEST_PROTOCOL_VERSION,
+                    "capabilities": { "roots": { "listChanged": true } },
+                    "clientInfo": { "name": "test-client", "version": "1.0.0" }
+                }),
+            ),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(session.roots().is_none());
+
+    // Answer each roots/list the server sends with the given roots
+    let answer = |call: Option<Call>, roots: Value| {
+        let Some(Call::MethodCall(call)) = call else {
+            panic!("Expected roots/list request");
+        };
+        assert_eq!(call.method, "roots/list");
+        let response: JsonRpcResponse = serde_json::from_value(json!({
+            "jsonrpc": "2.0",
+            "id": call.id,
+            "result": { "roots": roots }
+        }))
+        .unwrap();
+        session.handle_response(response);
+    };
+    let notify = |method: &str| {
+        JsonRpcRequest::Single(Call::Notification(Notification {
+            jsonrpc: Some(Version::V2),
+            method: method.to_string(),
+            params: Params::None,
+        }))
+    };
+
+    server
+        .handle_session_request(&session, notify("notifications/initialized"))
+        .await
+        .unwrap();
+    answer(
+        rx.recv().await,
+        json!([{ "uri": "file:///home/user/project", "name": "project" }]),
+    );
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(
+        session.roots(),
+        Some(vec![Root {
+            uri: "file:///home/user/project".to_string(),
+            name: Some("project".to_string()),
+        }])
+    );
+
+    server
+        .handle_session_request(&session, notify("notifications/roots/list_changed"))
+        .await
+        .unwrap();
+    answer(rx.recv().await, json!([]));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(session.roots(), Some(vec![]));
+}
+
+#[tokio::test]
+async fn test_roots_not_requested_without_capability() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    let (tx, mut rx) = mpsc::channel(10);
+    let session = server.connect(tx);
+
+    server
+        .handle_session_request(
+            &session,
+            JsonRpcRequest::Single(Call::Notification(Notification {
+                jsonrpc: Some(Version::V2),
+                method: "notifications/initialized".to_string(),
+                params: Params::None,
+            })),
+        )
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(rx.try_recv().is_err());
+    assert!(session.roots().is_none());
+}
+
+#[test]
+fn test_content_serialization() {
+    let image = Content::image("iVBORw0KGgo=", "image/png").with_annotations(Annotations {
+        audience: Some(vec![Role::User]),
+        priority: Some(0.5),
+    });
+    assert_eq!(
+        serde_json::to_value(&image).unwrap(),
+        json!({
+            "type": "image",
+            "data": "iVBORw0KGgo=",
+            "mimeType": "image/png",
+            "annotations": { "audience": ["user"], "priority": 0.5 }
+        })
+    );
+
+    assert_eq!(
+        serde_json::to_value(Content::audio("UklGRg==", "audio/wav")).unwrap(),
+        json!({ "type": "audio", "data": "UklGRg==", "mimeType": "audio/wav" })
+    );
+
+    let resource = Content::resource(ResourceContents::Blob(BlobResourceContents {
+        uri: "file:///chart.png".to_string(),
+        mime_type: Some("image/png".to_string()),
+        blob: "iVBORw0KGgo=".to_string(),
+    }));
+    assert_eq!(
+        serde_json::to_value(&resource).unwrap(),
+        json!({
+            "type": "resource",
+            "resource": {
+                "uri": "file:///chart.png",
+                "mimeType": "image/png",
+                "blob": "iVBORw0KGgo="
+            }
+        })
+    );
+
+    let link: Content = serde_json::from_value(json!({
+        "type": "resource_link",
+        "uri": "file:///report.csv",
+        "name": "report.csv",
+        "mimeType": "text/csv"
+    }))
+    .unwrap();
+    match link {
+        Content::ResourceLink { uri, mime_type, .. } => {
+            assert_eq!(uri, "file:///report.csv");
+            assert_eq!(mime_type.as_deref(), Some("text/csv"));
+        }
+        _ => panic!("Expected resource link"),
+    }
+
+    let embedded: Content = serde_json::from_value(json!({
+        "type": "resource",
+        "resource": { "uri": "config://app", "text": "{}" }
+    }))
+    .unwrap();
+    assert!(matches!(
+        embedded,
+        Content::EmbeddedResource {
+            resource: ResourceContents::Text(_),
+            ..
+        }
+    ));
+}
+
+#[tokio::test]
+async fn test_tool_rich_content() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    server.register_tool(ChartTool).await;
+
+    let response = server
+        .handle_request(method_call("tools/call", json!({ "name": "chart_tool" })))
+        .await
+        .unwrap()
+        .unwrap();
+    let JsonRpcResponse::Single(Output::Success(success)) = response else {
+        panic!("Expected successful response");
+    };
+    assert_eq!(
+        success.result,
+        json!({
+            "content": [{ "type": "image", "data": "iVBORw0KGgo=", "mimeType": "image/png" }],
+            "isError": false
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_tool_annotations_listed() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    server.register_tool(MockTool).await;
+    server.register_tool(ChartTool).await;
+
+    let response = server
+        .handle_request(method_call("tools/list", Value::Null))
+        .await
+        .unwrap()
+        .unwrap();
+    let JsonRpcResponse::Single(Output::Success(success)) = response else {
+        panic!("Expected successful response");
+    };
+    let tools = success.result["tools"].as_array().unwrap();
+    let chart = tools.iter().find(|t| t["name"] == "chart_tool").unwrap();
+    assert_eq!(chart["title"], "Render chart");
+    assert_eq!(
+        chart["annotations"],
+        json!({ "readOnlyHint": true, "openWorldHint": false })
+    );
+
+    // Tools without hints leave the fields out entirely
+    let mock = tools.iter().find(|t| t["name"] == "mock_tool").unwrap();
+    assert!(mock.get("title").is_none());
+    assert!(mock.get("annotations").is_none());
+}
+
+/// Initialize a session with `protocol_version` and return the version the
+/// server answered with.
+async fn initialize_with(server: &McpServer, session: &Arc<Session>, version: &str) -> Value {
+    let response = server
+        .handle_session_request(
+            session,
+            method_call(
+                "initialize",
+                json!({
+                    "protocolVersion": version,
+                    "capabilities": {},
+                    "clientInfo": { "name": "test-client", "version": "1.0.0" }
+                }),
+            ),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    let JsonRpcResponse::Single(Output::Success(success)) = response else {
+        panic!("Expected successful response");
+    };
+    success.result["protocolVersion"].clone()
+}
+
+#[tokio::test]
+async fn test_protocol_version_negotiation() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+
+    for version in SUPPORTED_PROTOCOL_VERSIONS {
+        let (tx, _rx) = mpsc::channel(10);
+        let session = server.connect(tx);
+        assert_eq!(initialize_with(&server, &session, version).await, *version);
+        assert_eq!(session.protocol_version(), *version);
+    }
+
+    // Unknown versions get the newest we support
+    let (tx, _rx) = mpsc::channel(10);
+    let session = server.connect(tx);
+    assert_eq!(
+        initialize_with(&server, &session, "1999-01-01").await,
+        LATEST_PROTOCOL_VERSION
+    );
+    assert_eq!(session.protocol_version(), LATEST_PROTOCOL_VERSION);
+}
+
+#[tokio::test]
+async fn test_features_gated_by_protocol_version() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    server.register_tool(MediaTool).await;
+
+    let list_and_call = |version: &'static str| {
+        let server = server.clone();
+        async move {
+            let (tx, _rx) = mpsc::channel(10);
+            let session = server.connect(tx);
+            initialize_with(&server, &session, version).await;
+            let mut results = Vec::new();
+            for request in [
+                method_call("tools/list", Value::Null),
+                method_call("tools/call", json!({ "name": "media_tool" })),
+            ] {
+                let response = server
+                    .handle_session_request(&session, request)
+                    .await
+                    .unwrap()
+                    .unwrap();
+                let JsonRpcResponse::Single(Output::Success(success)) = response else {
+                    panic!("Expected successful response");
+                };
+                results.push(success.result);
+            }
+            (results[0]["tools"][0].clone(), results[1].clone())
+        }
+    };
+
+    let (tool, result) = list_and_call("2025-06-18").await;
+    assert_eq!(tool["title"], "Fetch recording");
+    assert_eq!(tool["outputSchema"], json!({ "type": "object" }));
+    assert_eq!(tool["annotations"], json!({ "readOnlyHint": true }));
+    assert_eq!(result["structuredContent"], json!({ "seconds": 3 }));
+    assert_eq!(result["content"][0]["type"], "audio");
+    assert_eq!(result["content"][1]["type"], "resource_link");
+
+    let (tool, result) = list_and_call("2025-03-26").await;
+    assert!(tool.get("title").is_none());
+    assert!(tool.get("outputSchema").is_none());
+    assert_eq!(
+        tool["annotations"],
+        json!({ "title": "Fetch recording", "readOnlyHint": true })
+    );
+    assert!(result.get("structuredContent").is_none());
+    assert_eq!(result["content"][0]["type"], "audio");
+    assert_eq!(
+        result["content"][1],
+        json!({ "type": "text", "text": "take1.wav (file:///take1.wav)" })
+    );
+    // The structured value had no text fallback, so it becomes one
+    assert_eq!(
+        result["content"][2],
+        json!({ "type": "text", "text": "{\"seconds\":3}" })
+    );
+
+    let (tool, result) = list_and_call("2024-11-05").await;
+    assert!(tool.get("annotations").is_none());
+    assert_eq!(
+        result["content"],
+        json!([
+            { "type": "text", "text": "take1.wav (file:///take1.wav)" },
+            { "type": "text", "text": "{\"seconds\":3}" }
+        ])
+    );
+}
+
+#[tokio::test]
+async fn test_prompt_content_gated_by_protocol_version() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    server.register_prompt(MediaPrompt).await;
+
+    let get_prompt = |version: &'static str| {
+        let server = server.clone();
+        async move {
+            let (tx, _rx) = mpsc::channel(10);
+            let session = server.connect(tx);
+            initialize_with(&server, &session, version).await;
+            let response = server
+                .handle_session_request(
+                    &session,
+                    method_call("prompts/get", json!({ "name": "transcribe" })),
+                )
+                .await
+                .unwrap()
+                .unwrap();
+            let JsonRpcResponse::Single(Output::Success(success)) = response else {
+                panic!("Expected successful response");
+            };
+            success.result["messages"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|message| message["content"].clone())
+                .collect::<Vec<_>>()
+        }
+    };
+
+    let content = get_prompt("2025-06-18").await;
+    assert_eq!(content[1]["type"], "audio");
+    assert_eq!(content[2]["type"], "resource_link");
+
+    let content = get_prompt("2025-03-26").await;
+    assert_eq!(content[1]["type"], "audio");
+    assert_eq!(
+        content[2],
+        json!({ "type": "text", "text": "take1.wav (file:///take1.wav)" })
+    );
+
+    let content = get_prompt("2024-11-05").await;
+    assert_eq!(
+        content,
+        vec![
+            json!({ "type": "text", "text": "Transcribe this recording" }),
+            json!({ "type": "text", "text": "take1.wav (file:///take1.wav)" }),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_tool_title_moves_into_annotations() {
+    let server = Arc::new(McpServer::new("test-server", "1.0.0"));
+    server.register_tool(TitledTool).await;
+
+    let (tx, _rx) = mpsc::channel(10);
+    let session = server.connect(tx);
+    initialize_with(&server, &session, "2025-03-26").await;
+    let response = server
+        .handle_session_request(&session, method_call("tools/list", Value::Null))
+        .await
+        .unwrap()
+        .unwrap();
+    let JsonRpcResponse::Single(Output::Success(success)) = response else {
+        panic!("Expected successful response");
+    };
+    let tool = &success.result["tools"][0];
+    assert!(tool.get("title").is_none());
+    assert_eq!(tool["annotations"], json!({ "title": "Titled tool" }));
+}
@@ -2,7 +2,8 @@ use convert_case::{Case, Casing};
 use proc_macro::TokenStream;
 use quote::{format_ident, quote};
 use syn::{
-    parse_macro_input, Attribute, Expr, FnArg, ImplItem, ItemImpl, Lit, Meta, Pat, ReturnType, Type,
+    parse_macro_input, Attribute, Expr, FnArg, GenericArgument, ImplItem, ItemImpl, Lit, LitBool,
+    LitStr, Meta, Pat, PathArguments, ReturnType, Type,
 };
 
 fn extract_doc_string(attrs: &[Attribute]) -> String {
@@ -24,19 +25,16 @@ fn extract_doc_string(attrs: &[Attribute]) -> String {
 }
 
 fn get_type_schema(ty: &Type) -> proc_macro2::TokenStream {
-    match ty {
-        Type::Path(type_path) => {
-            if let Some(segment) = type_path.path.segments.last() {
-                if segment.ident == "Result" {
-                    if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
-                        if let Some(syn::GenericArgument::Type(ok_type)) = args.args.first() {
-                            return get_type_schema(ok_type);
-                        }
+    if let Type::Path(type_path) = ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            if segment.ident == "Result" {
+                if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                    if let Some(syn::GenericArgument::Type(ok_type)) = args.args.first() {
+                        return get_type_schema(ok_type);
                     }
                 }
             }
         }
-        _ => {}
     }
     quote! {
         <#ty as schemars::JsonSchema>::json_schema(&mut schemars::gen::SchemaGenerator::default())
@@ -45,7 +43,7 @@ fn get_type_schema(ty: &Type) -> proc_macro2::TokenStream {
 
 fn is_optional_type(ty: &Type) -> bool {
     matches!(ty, Type::Path(type_path) if type_path.path.segments.last()
-        .map_or(false, |segment| segment.ident == "Option"))
+        .is_some_and(|segment| segment.ident == "Option"))
 }
 
 fn extract_param_doc(docs: &str, param_name: &str) -> String {
@@ -125,12 +123,8 @@ fn generate_param_deserialization(param_name: &str, is_optional: bool) -> proc_m
     }
 }
 
-#[proc_macro_attribute]
-pub fn tool(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(item as ItemImpl);
-    let ty = &*input.self_ty;
-
-    let type_name = if let Type::Path(type_path) = ty {
+fn impl_type_name(ty: &Type) -> String {
+    if let Type::Path(type_path) = ty {
         type_path
             .path
             .segments
@@ -139,7 +133,156 @@ pub fn tool(_attr: TokenStream, item: TokenStream) -> TokenStream {
             .expect("Expected a type with at least one segment")
     } else {
         panic!("Expected a path type")
+    }
+}
+
+/// Parameters filled in from the call's `ToolContext` rather than from the
+/// JSON arguments. Returns the expression that produces the value.
+fn context_argument(ty: &Type) -> Option<proc_macro2::TokenStream> {
+    let (ty, by_ref) = match ty {
+        Type::Reference(reference) => (&*reference.elem, true),
+        ty => (ty, false),
+    };
+    let Type::Path(type_path) = ty else {
+        return None;
     };
+    match (
+        type_path.path.segments.last()?.ident.to_string().as_str(),
+        by_ref,
+    ) {
+        ("ToolContext", true) => Some(quote! { &ctx }),
+        ("ToolContext", false) => Some(quote! { ctx.clone() }),
+        ("CancellationToken", true) => Some(quote! { ctx.cancellation_token() }),
+        ("CancellationToken", false) => Some(quote! { ctx.cancellation_token().clone() }),
+        _ => None,
+    }
+}
+
+fn is_result_type(output: &ReturnType) -> bool {
+    matches!(output, ReturnType::Type(_, ty) if matches!(ty.as_ref(), Type::Path(p) if p.path.segments.last().is_some_and(|s| s.ident == "Result")))
+}
+
+/// The type a method produces on success: `T` for `Result<T, E>`, otherwise
+/// the return type itself.
+fn success_type(output: &ReturnType) -> Option<&Type> {
+    let ReturnType::Type(_, ty) = output else {
+        return None;
+    };
+    if !is_result_type(output) {
+        return Some(ty);
+    }
+    match ty.as_ref() {
+        Type::Path(type_path) => match &type_path.path.segments.last()?.arguments {
+            PathArguments::AngleBracketed(args) => match args.args.first()? {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// How a tool method's successful return value becomes a `CallToolResult`.
+enum ToolOutput<'a> {
+    /// Rendered with `Display`
+    Text,
+    /// `Content`, `Vec<Content>` or a `CallToolResult` built by the method
+    Content,
+    /// Serialized as structured content, for methods marked `#[tool(structured)]`
+    Structured(&'a Type),
+}
+
+fn tool_output(output: &ReturnType, structured: bool) -> ToolOutput<'_> {
+    let Some(ty) = success_type(output) else {
+        return ToolOutput::Text;
+    };
+    let fallback = if structured {
+        ToolOutput::Structured(ty)
+    } else {
+        ToolOutput::Text
+    };
+    let Type::Path(type_path) = ty else {
+        return fallback;
+    };
+    let Some(segment) = type_path.path.segments.last() else {
+        return fallback;
+    };
+    match segment.ident.to_string().as_str() {
+        "Content" | "CallToolResult" => ToolOutput::Content,
+        "Vec"
+            if matches!(
+                &segment.arguments,
+                PathArguments::AngleBracketed(args) if matches!(
+                    args.args.first(),
+                    Some(GenericArgument::Type(Type::Path(inner)))
+                        if inner.path.segments.last().is_some_and(|s| s.ident == "Content")
+                )
+            ) =>
+        {
+            ToolOutput::Content
+        }
+        _ => fallback,
+    }
+}
+
+/// Per-method `#[tool(...)]` options inside a `#[tool]` impl.
+#[derive(Default)]
+struct ToolAttr {
+    title: Option<String>,
+    read_only: Option<bool>,
+    destructive: Option<bool>,
+    idempotent: Option<bool>,
+    open_world: Option<bool>,
+    structured: bool,
+}
+
+fn is_tool_attr(attr: &Attribute) -> bool {
+    attr.path().is_ident("tool") || attr.path().is_ident("mcp_tool")
+}
+
+fn parse_tool_attr(attrs: &[Attribute]) -> syn::Result<ToolAttr> {
+    let mut tool_attr = ToolAttr::default();
+    for attr in attrs.iter().filter(|a| is_tool_attr(a)) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("title") {
+                tool_attr.title = Some(meta.value()?.parse::<LitStr>()?.value());
+                return Ok(());
+            }
+            if meta.path.is_ident("structured") {
+                tool_attr.structured = true;
+                return Ok(());
+            }
+            let hint = if meta.path.is_ident("read_only") {
+                &mut tool_attr.read_only
+            } else if meta.path.is_ident("destructive") {
+                &mut tool_attr.destructive
+            } else if meta.path.is_ident("idempotent") {
+                &mut tool_attr.idempotent
+            } else if meta.path.is_ident("open_world") {
+                &mut tool_attr.open_world
+            } else {
+                return Err(meta.error(
+                    "expected `title`, `read_only`, `destructive`, `idempotent`, `open_world` or `structured`",
+                ));
+            };
+            // A bare flag means `true`; `flag = false` overrides the client's default
+            *hint = Some(if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<LitBool>()?.value
+            } else {
+                true
+            });
+            Ok(())
+        })?;
+    }
+    Ok(tool_attr)
+}
+
+#[proc_macro_attribute]
+pub fn tool(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(item as ItemImpl);
+    let ty = &*input.self_ty;
+    let type_name = impl_type_name(ty);
 
     let mut tool_impls = Vec::new();
     let mut tool_names = Vec::new();
@@ -156,15 +299,53 @@ pub fn tool(_attr: TokenStream, item: TokenStream) -> TokenStream {
             tool_names.push(tool_struct_name.clone());
 
             let docs = extract_doc_string(&method.attrs);
+            let tool_attr = match parse_tool_attr(&method.attrs) {
+                Ok(tool_attr) => tool_attr,
+                Err(e) => return e.to_compile_error().into(),
+            };
+            let title = match &tool_attr.title {
+                Some(title) => quote! { Some(#title) },
+                None => quote! { None },
+            };
+            let hint = |value: Option<bool>| match value {
+                Some(value) => quote! { Some(#value) },
+                None => quote! { None },
+            };
+            let annotations_impl = if tool_attr.title.is_none()
+                && tool_attr.read_only.is_none()
+                && tool_attr.destructive.is_none()
+                && tool_attr.idempotent.is_none()
+                && tool_attr.open_world.is_none()
+            {
+                quote! { None }
+            } else {
+                let read_only = hint(tool_attr.read_only);
+                let destructive = hint(tool_attr.destructive);
+                let idempotent = hint(tool_attr.idempotent);
+                let open_world = hint(tool_attr.open_world);
+                quote! {
+                    Some(offeryn_types::ToolAnnotations {
+                        title: self.title().map(str::to_string),
+                        read_only_hint: #read_only,
+                        destructive_hint: #destructive,
+                        idempotent_hint: #idempotent,
+                        open_world_hint: #open_world,
+                    })
+                }
+            };
 
             let mut param_schemas = Vec::new();
-            let mut param_desers = Vec::new();
+            let mut call_args = Vec::new();
 
             for param in &method.sig.inputs {
                 if let FnArg::Typed(pat_type) = param {
                     if let Pat::Ident(param_name) = &*pat_type.pat {
                         if param_name.ident != "self" {
                             let param_type = &*pat_type.ty;
+                            if let Some(arg) = context_argument(param_type) {
+                                call_args.push(arg);
+                                continue;
+                            }
                             let name_str = param_name.ident.to_string();
                             let is_optional = is_optional_type(param_type);
                             let param_doc = extract_param_doc(&docs, &name_str);
@@ -175,59 +356,60 @@ pub fn tool(_attr: TokenStream, item: TokenStream) -> TokenStream {
                                 &param_doc,
                                 is_optional,
                             ));
-                            param_desers
-                                .push(generate_param_deserialization(&name_str, is_optional));
+                            call_args.push(generate_param_deserialization(&name_str, is_optional));
                         }
                     }
                 }
             }
 
-            let is_result = matches!(&method.sig.output, ReturnType::Type(_, ty) if matches!(ty.as_ref(), Type::Path(p) if p.path.segments.last().map_or(false, |s| s.ident == "Result")));
+            let is_result = is_result_type(&method.sig.output);
+            let output = tool_output(&method.sig.output, tool_attr.structured);
+            let into_result = match output {
+                ToolOutput::Text => {
+                    quote! { offeryn_types::CallToolResult::text(result.to_string()) }
+                }
+                ToolOutput::Content => {
+                    quote! { offeryn_types::IntoToolResult::into_tool_result(result) }
+                }
+                ToolOutput::Structured(_) => quote! {
+                    offeryn_types::CallToolResult::structured(
+                        serde_json::to_value(&result).map_err(|e| e.to_string())?,
+                    )
+                },
+            };
+            let output_schema_impl = match output {
+                // Clients only accept object schemas for structured content
+                ToolOutput::Structured(ty) => quote! {
+                    let schema = serde_json::to_value(schemars::schema_for!(#ty)).ok()?;
+                    (schema["type"] == "object").then_some(schema)
+                },
+                _ => quote! { None },
+            };
 
             let result_handling = if is_result {
                 quote! {
                     match result {
-                        Ok(result) => Ok(offeryn_types::ToolResult {
-                            content: vec![offeryn_types::ToolContent {
-                                r#type: "text".to_string(),
-                                text: result.to_string(),
-                            }],
-                            is_error: false,
-                        }),
-                        Err(e) => Ok(offeryn_types::ToolResult {
-                            content: vec![offeryn_types::ToolContent {
-                                r#type: "text".to_string(),
-                                text: e.to_string(),
-                            }],
-                            is_error: true,
-                        })
+                        Ok(result) => Ok(#into_result),
+                        Err(e) => Ok(offeryn_types::CallToolResult::error(e.to_string())),
                     }
                 }
             } else {
-                quote! {
-                    Ok(offeryn_types::ToolResult {
-                        content: vec![offeryn_types::ToolContent {
-                            r#type: "text".to_string(),
-                            text: result.to_string(),
-                        }],
-                        is_error: false,
-                    })
-                }
+                quote! { Ok(#into_result) }
             };
 
-            let execute_impl = if param_desers.is_empty() {
+            let execute_impl = if param_schemas.is_empty() {
                 quote! {
                     let args = args.as_object().ok_or("Expected object")?;
                     if !args.is_empty() {
                         return Err("Expected no arguments".to_string());
                     }
-                    let result = self.inner.#method_name().await;
+                    let result = self.inner.#method_name(#(#call_args),*).await;
                     #result_handling
                 }
             } else {
                 quote! {
                     let args = args.as_object().ok_or("Expected object")?;
-                    let result = self.inner.#method_name(#(#param_desers),*).await;
+                    let result = self.inner.#method_name(#(#call_args),*).await;
                     #result_handling
                 }
             };
@@ -273,7 +455,15 @@ pub fn tool(_attr: TokenStream, item: TokenStream) -> TokenStream {
                     fn name(&self) -> &str { #tool_name }
                     fn description(&self) -> &str { #docs }
                     fn input_schema(&self) -> serde_json::Value { #schema_impl }
-                    async fn execute(&self, args: serde_json::Value) -> Result<offeryn_types::ToolResult, String> {
+                    fn output_schema(&self) -> Option<serde_json::Value> { #output_schema_impl }
+                    fn title(&self) -> Option<&str> { #title }
+                    fn annotations(&self) -> Option<offeryn_types::ToolAnnotations> { #annotations_impl }
+                    #[allow(unused_variables)]
+                    async fn execute(
+                        &self,
+                        args: serde_json::Value,
+                        ctx: offeryn_types::ToolContext,
+                    ) -> Result<offeryn_types::CallToolResult, String> {
                         #execute_impl
                     }
                 }
@@ -283,6 +473,14 @@ pub fn tool(_attr: TokenStream, item: TokenStream) -> TokenStream {
         }
     }
 
+    // Strip the per-method `#[tool(...)]` attributes before re-emitting the impl.
+    for item in &mut input.items {
+        if let ImplItem::Fn(method) = item {
+            method.attrs.retain(|a| !is_tool_attr(a));
+        }
+    }
+    let ty = &*input.self_ty;
+
     TokenStream::from(quote! {
         #input
 
@@ -299,3 +497,451 @@ pub fn tool(_attr: TokenStream, item: TokenStream) -> TokenStream {
         #(#tool_impls)*
     })
 }
+
+fn generate_string_argument_parse(param_name: &str, is_optional: bool) -> proc_macro2::TokenStream {
+    let name_str = param_name.to_string();
+    // Prompt arguments and URI template variables always arrive as strings; try them as JSON strings first
+    // so `String` parameters work, then as JSON literals for numbers and the like.
+    let parse = quote! {
+        serde_json::from_value(serde_json::Value::String(raw.clone()))
+            .or_else(|_| serde_json::from_str(raw))
+            .map_err(|e| format!("Invalid value for argument {}: {}", #name_str, e))?
+    };
+    if is_optional {
+        quote! {
+            match args.get(#name_str) {
+                Some(raw) => Some(#parse),
+                None => None
+            }
+        }
+    } else {
+        quote! {
+            {
+                let raw = args
+                    .get(#name_str)
+                    .ok_or_else(|| format!("Missing required argument: {}", #name_str))?;
+                #parse
+            }
+        }
+    }
+}
+
+#[proc_macro_attribute]
+pub fn prompt(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as ItemImpl);
+    let ty = &*input.self_ty;
+    let type_name = impl_type_name(ty);
+
+    let mut prompt_impls = Vec::new();
+    let mut prompt_names = Vec::new();
+
+    for item in &input.items {
+        if let ImplItem::Fn(method) = item {
+            let method_name = &method.sig.ident;
+            let prompt_name = format!("{}_{}", type_name.to_case(Case::Snake), method_name);
+            let prompt_struct_name = format_ident!(
+                "{}{}Prompt",
+                type_name.to_case(Case::Pascal),
+                method_name.to_string().to_case(Case::Pascal)
+            );
+            prompt_names.push(prompt_struct_name.clone());
+
+            let docs = extract_doc_string(&method.attrs);
+
+            let mut arguments = Vec::new();
+            let mut arg_parses = Vec::new();
+
+            for param in &method.sig.inputs {
+                if let FnArg::Typed(pat_type) = param {
+                    if let Pat::Ident(param_name) = &*pat_type.pat {
+                        if param_name.ident != "self" {
+                            let name_str = param_name.ident.to_string();
+                            let is_optional = is_optional_type(&pat_type.ty);
+                            let param_doc = extract_param_doc(&docs, &name_str);
+                            let description = if param_doc.is_empty() {
+                                quote! { None }
+                            } else {
+                                quote! { Some(#param_doc.to_string()) }
+                            };
+                            let required = !is_optional;
+
+                            arguments.push(quote! {
+                                offeryn_types::PromptArgument {
+                                    name: #name_str.to_string(),
+                                    description: #description,
+                                    required: Some(#required),
+                                }
+                            });
+                            arg_parses.push(generate_string_argument_parse(&name_str, is_optional));
+                        }
+                    }
+                }
+            }
+
+            let result_handling = if is_result_type(&method.sig.output) {
+                quote! {
+                    result
+                        .map(offeryn_types::IntoPromptMessages::into_prompt_messages)
+                        .map_err(|e| e.to_string())
+                }
+            } else {
+                quote! {
+                    Ok(offeryn_types::IntoPromptMessages::into_prompt_messages(result))
+                }
+            };
+
+            let prompt_impl = quote! {
+                #[doc(hidden)]
+                pub struct #prompt_struct_name {
+                    inner: std::sync::Arc<#ty>,
+                }
+
+                impl #prompt_struct_name {
+                    pub fn new(inner: std::sync::Arc<#ty>) -> Self {
+                        Self { inner }
+                    }
+                }
+
+                #[async_trait::async_trait]
+                impl offeryn_types::McpPrompt for #prompt_struct_name {
+                    fn name(&self) -> &str { #prompt_name }
+                    fn description(&self) -> &str { #docs }
+                    fn arguments(&self) -> Vec<offeryn_types::PromptArgument> {
+                        vec![#(#arguments),*]
+                    }
+                    async fn render(
+                        &self,
+                        args: std::collections::HashMap<String, String>,
+                    ) -> Result<Vec<offeryn_types::PromptMessage>, String> {
+                        let result = self.inner.#method_name(#(#arg_parses),*).await;
+                        #result_handling
+                    }
+                }
+            };
+
+            prompt_impls.push(prompt_impl);
+        }
+    }
+
+    TokenStream::from(quote! {
+        #input
+
+        impl offeryn_types::HasPrompts for #ty {
+            type Prompts = Vec<Box<dyn offeryn_types::McpPrompt>>;
+            fn prompts(self) -> Self::Prompts {
+                let this = std::sync::Arc::new(self);
+                vec![
+                    #(Box::new(#prompt_names::new(this.clone()))),*
+                ]
+            }
+        }
+
+        #(#prompt_impls)*
+    })
+}
+
+struct ResourceAttr {
+    uri: LitStr,
+    name: Option<String>,
+    mime_type: Option<String>,
+}
+
+fn is_resource_attr(attr: &Attribute) -> bool {
+    attr.path().is_ident("resource") || attr.path().is_ident("mcp_resource")
+}
+
+fn parse_resource_attr(attr: &Attribute) -> syn::Result<ResourceAttr> {
+    let mut uri = None;
+    let mut name = None;
+    let mut mime_type = None;
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("uri") {
+            uri = Some(meta.value()?.parse::<LitStr>()?);
+        } else if meta.path.is_ident("name") {
+            name = Some(meta.value()?.parse::<LitStr>()?.value());
+        } else if meta.path.is_ident("mime_type") {
+            mime_type = Some(meta.value()?.parse::<LitStr>()?.value());
+        } else {
+            return Err(meta.error("expected `uri`, `name` or `mime_type`"));
+        }
+        Ok(())
+    })?;
+    let uri = uri.ok_or_else(|| syn::Error::new_spanned(attr, "missing `uri = \"...\"`"))?;
+    Ok(ResourceAttr {
+        uri,
+        name,
+        mime_type,
+    })
+}
+
+enum UriSegment {
+    Literal(String),
+    /// A variable; `reserved` (`{+var}`) values may span `/`.
+    Variable {
+        name: String,
+        reserved: bool,
+    },
+}
+
+/// Split an RFC 6570 level 1 template (`{var}`, or `{+var}` for reserved
+/// expansion) into literal and variable segments.
+fn parse_uri_template(template: &str) -> Result<Vec<UriSegment>, String> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            segments.push(UriSegment::Literal(rest[..start].to_string()));
+        } else if matches!(segments.last(), Some(UriSegment::Variable { .. })) {
+            return Err("adjacent template variables cannot be matched".to_string());
+        }
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| "unterminated template variable".to_string())?
+            + start;
+        let expression = &rest[start + 1..end];
+        let variable = expression.strip_prefix('+').unwrap_or(expression);
+        if variable.is_empty() || !variable.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(format!(
+                "unsupported template expression `{}`",
+                &rest[start..=end]
+            ));
+        }
+        segments.push(UriSegment::Variable {
+            name: variable.to_string(),
+            reserved: variable.len() < expression.len(),
+        });
+        rest = &rest[end + 1..];
+    }
+    if !rest.is_empty() {
+        segments.push(UriSegment::Literal(rest.to_string()));
+    }
+    Ok(segments)
+}
+
+/// Generate a function body that matches `uri` against the template segments,
+/// returning the percent-decoded captures.
+fn generate_uri_matcher(segments: &[UriSegment]) -> proc_macro2::TokenStream {
+    let steps = segments
+        .iter()
+        .enumerate()
+        .map(|(i, segment)| match segment {
+            UriSegment::Literal(literal) => quote! {
+                let rest = rest.strip_prefix(#literal)?;
+            },
+            UriSegment::Variable { name, reserved } => {
+                let (end, next) = match segments.get(i + 1) {
+                    Some(UriSegment::Literal(next)) => {
+                        (quote! { rest.find(#next)? }, quote! { &rest[end..] })
+                    }
+                    _ => (quote! { rest.len() }, quote! { "" }),
+                };
+                quote! {
+                    let end = #end;
+                    let value = &rest[..end];
+                    if value.is_empty() || (!#reserved && value.contains('/')) {
+                        return None;
+                    }
+                    captures.insert(#name.to_string(), offeryn_types::percent_decode(value)?);
+                    let rest = #next;
+                }
+            }
+        });
+    quote! {
+        let mut captures = std::collections::HashMap::new();
+        let rest = uri;
+        #(#steps)*
+        if !rest.is_empty() {
+            return None;
+        }
+        Some(captures)
+    }
+}
+
+#[proc_macro_attribute]
+pub fn resource(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(item as ItemImpl);
+    let ty = &*input.self_ty;
+    let type_name = impl_type_name(ty);
+
+    let mut resource_impls = Vec::new();
+    let mut resource_entries = Vec::new();
+
+    for item in &input.items {
+        let ImplItem::Fn(method) = item else {
+            continue;
+        };
+        // Methods without a `#[resource(...)]` attribute are left alone.
+        let Some(attr) = method.attrs.iter().find(|a| is_resource_attr(a)) else {
+            continue;
+        };
+        let resource_attr = match parse_resource_attr(attr) {
+            Ok(resource_attr) => resource_attr,
+            Err(e) => return e.to_compile_error().into(),
+        };
+
+        let method_name = &method.sig.ident;
+        let uri = resource_attr.uri.value();
+        let resource_name = resource_attr
+            .name
+            .unwrap_or_else(|| format!("{}_{}", type_name.to_case(Case::Snake), method_name));
+        let mime_type = match &resource_attr.mime_type {
+            Some(mime_type) => quote! { Some(#mime_type) },
+            None => quote! { None },
+        };
+        let resource_struct_name = format_ident!(
+            "{}{}Resource",
+            type_name.to_case(Case::Pascal),
+            method_name.to_string().to_case(Case::Pascal)
+        );
+        let docs = extract_doc_string(&method.attrs);
+
+        let segments = match parse_uri_template(&uri) {
+            Ok(segments) => segments,
+            Err(e) => {
+                return syn::Error::new_spanned(&resource_attr.uri, e)
+                    .to_compile_error()
+                    .into()
+            }
+        };
+        let variables: Vec<&str> = segments
+            .iter()
+            .filter_map(|segment| match segment {
+                UriSegment::Variable { name, .. } => Some(name.as_str()),
+                UriSegment::Literal(_) => None,
+            })
+            .collect();
+
+        let mut arg_parses = Vec::new();
+        for param in &method.sig.inputs {
+            if let FnArg::Typed(pat_type) = param {
+                if let Pat::Ident(param_name) = &*pat_type.pat {
+                    let name_str = param_name.ident.to_string();
+                    if !variables.contains(&name_str.as_str()) {
+                        return syn::Error::new_spanned(
+                            param_name,
+                            format!(
+                                "parameter `{}` does not appear in the URI template",
+                                name_str
+                            ),
+                        )
+                        .to_compile_error()
+                        .into();
+                    }
+                    arg_parses.push(generate_string_argument_parse(
+                        &name_str,
+                        is_optional_type(&pat_type.ty),
+                    ));
+                }
+            }
+        }
+
+        let result_handling = if is_result_type(&method.sig.output) {
+            quote! {
+                result
+                    .map(|r| offeryn_types::IntoResourceContents::into_resource_contents(r, uri, #mime_type))
+                    .map_err(|e| e.to_string())
+            }
+        } else {
+            quote! {
+                Ok(offeryn_types::IntoResourceContents::into_resource_contents(result, uri, #mime_type))
+            }
+        };
+
+        let (trait_impl, entry) = if variables.is_empty() {
+            (
+                quote! {
+                    #[async_trait::async_trait]
+                    impl offeryn_types::McpResource for #resource_struct_name {
+                        fn uri(&self) -> &str { #uri }
+                        fn name(&self) -> &str { #resource_name }
+                        fn description(&self) -> Option<&str> {
+                            Some(#docs).filter(|d| !d.is_empty())
+                        }
+                        fn mime_type(&self) -> Option<&str> { #mime_type }
+                        async fn read(&self) -> Result<Vec<offeryn_types::ResourceContents>, String> {
+                            let uri = #uri;
+                            let result = self.inner.#method_name().await;
+                            #result_handling
+                        }
+                    }
+                },
+                quote! {
+                    offeryn_types::ResourceEntry::Static(Box::new(#resource_struct_name::new(this.clone())))
+                },
+            )
+        } else {
+            let matcher = generate_uri_matcher(&segments);
+            (
+                quote! {
+                    impl #resource_struct_name {
+                        fn capture(uri: &str) -> Option<std::collections::HashMap<String, String>> {
+                            #matcher
+                        }
+                    }
+
+                    #[async_trait::async_trait]
+                    impl offeryn_types::McpResourceTemplate for #resource_struct_name {
+                        fn uri_template(&self) -> &str { #uri }
+                        fn name(&self) -> &str { #resource_name }
+                        fn description(&self) -> Option<&str> {
+                            Some(#docs).filter(|d| !d.is_empty())
+                        }
+                        fn mime_type(&self) -> Option<&str> { #mime_type }
+                        fn matches(&self, uri: &str) -> bool {
+                            Self::capture(uri).is_some()
+                        }
+                        async fn read(&self, uri: &str) -> Result<Vec<offeryn_types::ResourceContents>, String> {
+                            let args = Self::capture(uri)
+                                .ok_or_else(|| format!("URI does not match template: {}", uri))?;
+                            let result = self.inner.#method_name(#(#arg_parses),*).await;
+                            #result_handling
+                        }
+                    }
+                },
+                quote! {
+                    offeryn_types::ResourceEntry::Template(Box::new(#resource_struct_name::new(this.clone())))
+                },
+            )
+        };
+
+        resource_entries.push(entry);
+        resource_impls.push(quote! {
+            #[doc(hidden)]
+            pub struct #resource_struct_name {
+                inner: std::sync::Arc<#ty>,
+            }
+
+            impl #resource_struct_name {
+                pub fn new(inner: std::sync::Arc<#ty>) -> Self {
+                    Self { inner }
+                }
+            }
+
+            #trait_impl
+        });
+    }
+
+    // Strip the per-method `#[resource(...)]` attributes before re-emitting the impl.
+    for item in &mut input.items {
+        if let ImplItem::Fn(method) = item {
+            method.attrs.retain(|a| !is_resource_attr(a));
+        }
+    }
+    let ty = &*input.self_ty;
+
+    TokenStream::from(quote! {
+        #input
+
+        impl offeryn_types::HasResources for #ty {
+            type Resources = Vec<offeryn_types::ResourceEntry>;
+            fn resources(self) -> Self::Resources {
+                let this = std::sync::Arc::new(self);
+                vec![
+                    #(#resource_entries),*
+                ]
+            }
+        }
+
+        #(#resource_impls)*
+    })
+}
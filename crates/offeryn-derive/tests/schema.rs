@@ -4,4 +4,11 @@ fn test_schema() {
     t.pass("tests/schema/01-basic.rs");
     t.pass("tests/schema/02-doc-comments.rs");
     t.pass("tests/schema/03-stateful.rs");
+    t.pass("tests/schema/04-prompts.rs");
+    t.pass("tests/schema/05-resources.rs");
+    t.pass("tests/schema/06-context.rs");
+    t.pass("tests/schema/07-content.rs");
+    t.pass("tests/schema/08-structured.rs");
+    t.pass("tests/schema/09-annotations.rs");
+    t.pass("tests/schema/10-display.rs");
 }
@@ -0,0 +1,100 @@
+use offeryn_derive::prompt;
+use offeryn_types::*;
+use std::collections::HashMap;
+
+/// Reusable prompts for code review
+#[derive(Default)]
+struct ReviewPrompts {}
+
+#[prompt]
+impl ReviewPrompts {
+    /// Ask for a review of a code snippet
+    ///
+    /// # Parameters
+    /// * `code` - The code to review
+    /// * `language` - Language the code is written in
+    async fn review(&self, code: String, language: Option<String>) -> String {
+        format!(
+            "Review this {} code:\n{}",
+            language.unwrap_or_else(|| "unknown".to_string()),
+            code
+        )
+    }
+
+    /// Summarise the last few commits
+    ///
+    /// # Parameters
+    /// * `count` - Number of commits to summarise
+    async fn summarise(&self, count: u32) -> Result<Vec<PromptMessage>, String> {
+        if count == 0 {
+            return Err("count must be positive".to_string());
+        }
+        Ok(vec![
+            PromptMessage {
+                role: Role::User,
+                content: Content::text(format!("Summarise the last {} commits", count)),
+            },
+            PromptMessage {
+                role: Role::Assistant,
+                content: Content::text("Sure, here is a summary:"),
+            },
+        ])
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let prompts = ReviewPrompts::default().prompts();
+
+    // Test review prompt metadata
+    let review = &prompts[0];
+    assert_eq!(review.name(), "review_prompts_review");
+    assert!(review
+        .description()
+        .starts_with("Ask for a review of a code snippet"));
+
+    let arguments = review.arguments();
+    assert_eq!(arguments.len(), 2);
+    assert_eq!(arguments[0].name, "code");
+    assert_eq!(arguments[0].description.as_deref(), Some("The code to review"));
+    assert_eq!(arguments[0].required, Some(true));
+    assert_eq!(arguments[1].name, "language");
+    assert_eq!(
+        arguments[1].description.as_deref(),
+        Some("Language the code is written in")
+    );
+    assert_eq!(arguments[1].required, Some(false));
+
+    // Test rendering with string arguments
+    let mut args = HashMap::new();
+    args.insert("code".to_string(), "fn main() {}".to_string());
+    let messages = review.render(args).await.unwrap();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].role, Role::User);
+    match &messages[0].content {
+        Content::Text { text, .. } => assert_eq!(text, "Review this unknown code:\nfn main() {}"),
+        _ => panic!("Expected text content"),
+    }
+
+    // Missing required arguments are reported
+    assert!(review.render(HashMap::new()).await.is_err());
+
+    // Test typed arguments parsed from strings
+    let summarise = &prompts[1];
+    let mut args = HashMap::new();
+    args.insert("count".to_string(), "3".to_string());
+    let messages = summarise.render(args).await.unwrap();
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[1].role, Role::Assistant);
+
+    let mut args = HashMap::new();
+    args.insert("count".to_string(), "three".to_string());
+    assert!(summarise.render(args).await.is_err());
+
+    let mut args = HashMap::new();
+    args.insert("count".to_string(), "0".to_string());
+    assert_eq!(
+        summarise.render(args).await.unwrap_err(),
+        "count must be positive"
+    );
+}
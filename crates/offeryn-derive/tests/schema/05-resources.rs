@@ -0,0 +1,107 @@
+use offeryn_derive::resource;
+use offeryn_types::*;
+
+/// A file store exposing its contents as resources
+#[derive(Default)]
+struct FileStore {}
+
+#[resource]
+impl FileStore {
+    /// Application configuration
+    #[resource(uri = "config://app", mime_type = "application/json")]
+    async fn config(&self) -> String {
+        r#"{"debug":true}"#.to_string()
+    }
+
+    /// Read a file from the store
+    #[resource(uri = "file:///{+path}", name = "files")]
+    async fn read_file(&self, path: String) -> Result<String, String> {
+        if path == "missing.txt" {
+            Err(format!("No such file: {}", path))
+        } else {
+            Ok(format!("contents of {}", path))
+        }
+    }
+
+    /// Read a single row from a table
+    #[mcp_resource(uri = "db://{table}/rows/{id}")]
+    async fn row(&self, table: String, id: u64) -> String {
+        format!("{}#{}", table, id)
+    }
+
+    fn helper(&self) -> u32 {
+        42
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    assert_eq!(FileStore::default().helper(), 42);
+
+    let mut statics = Vec::new();
+    let mut templates = Vec::new();
+    for entry in FileStore::default().resources() {
+        match entry {
+            ResourceEntry::Static(resource) => statics.push(resource),
+            ResourceEntry::Template(template) => templates.push(template),
+        }
+    }
+    assert_eq!(statics.len(), 1);
+    assert_eq!(templates.len(), 2);
+
+    // Test static resource
+    let config = &statics[0];
+    assert_eq!(config.uri(), "config://app");
+    assert_eq!(config.name(), "file_store_config");
+    assert_eq!(config.description(), Some("Application configuration"));
+    assert_eq!(config.mime_type(), Some("application/json"));
+    match &config.read().await.unwrap()[0] {
+        ResourceContents::Text(contents) => {
+            assert_eq!(contents.uri, "config://app");
+            assert_eq!(contents.mime_type.as_deref(), Some("application/json"));
+            assert_eq!(contents.text, r#"{"debug":true}"#);
+        }
+        ResourceContents::Blob(_) => panic!("Expected text contents"),
+    }
+
+    // Test single-variable template
+    let files = &templates[0];
+    assert_eq!(files.uri_template(), "file:///{+path}");
+    assert_eq!(files.name(), "files");
+    assert!(files.matches("file:///notes/today.txt"));
+    assert!(!files.matches("file:///"));
+    assert!(!files.matches("http://example.com"));
+    match &files.read("file:///notes/today.txt").await.unwrap()[0] {
+        ResourceContents::Text(contents) => {
+            assert_eq!(contents.uri, "file:///notes/today.txt");
+            assert_eq!(contents.text, "contents of notes/today.txt");
+        }
+        ResourceContents::Blob(_) => panic!("Expected text contents"),
+    }
+    assert_eq!(
+        files.read("file:///missing.txt").await.unwrap_err(),
+        "No such file: missing.txt"
+    );
+    // Captures are percent-decoded
+    match &files.read("file:///my%20notes/caf%C3%A9.txt").await.unwrap()[0] {
+        ResourceContents::Text(contents) => {
+            assert_eq!(contents.text, "contents of my notes/café.txt")
+        }
+        ResourceContents::Blob(_) => panic!("Expected text contents"),
+    }
+    assert!(!files.matches("file:///100%.txt"));
+
+    // Test multi-variable template with typed arguments
+    let rows = &templates[1];
+    assert!(rows.matches("db://users/rows/7"));
+    assert!(!rows.matches("db://users/rows/"));
+    assert!(!rows.matches("db://users/columns/7"));
+    // Simple variables stop at `/`
+    assert!(!rows.matches("db://app/users/rows/7"));
+    assert!(!rows.matches("db://users/rows/7/8"));
+    match &rows.read("db://users/rows/7").await.unwrap()[0] {
+        ResourceContents::Text(contents) => assert_eq!(contents.text, "users#7"),
+        ResourceContents::Blob(_) => panic!("Expected text contents"),
+    }
+    assert!(rows.read("db://users/rows/seven").await.is_err());
+}
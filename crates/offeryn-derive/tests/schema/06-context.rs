@@ -0,0 +1,61 @@
+use offeryn_derive::tool;
+use offeryn_types::*;
+
+/// A tool that can be interrupted part way through
+#[derive(Default)]
+struct Crawler {}
+
+#[tool]
+impl Crawler {
+    /// Crawl a number of pages
+    ///
+    /// # Parameters
+    /// * `pages` - How many pages to visit
+    async fn crawl(&self, pages: u32, cancel: CancellationToken) -> Result<u32, String> {
+        for page in 0..pages {
+            if cancel.is_cancelled() {
+                return Err(format!("Cancelled after {} pages", page));
+            }
+        }
+        Ok(pages)
+    }
+
+    /// Report which session is calling
+    async fn whoami(&self, ctx: &ToolContext) -> String {
+        ctx.session_id().unwrap_or("anonymous").to_string()
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let tools = Crawler::default().tools();
+    let crawl = &tools[0];
+
+    // The token is injected by the server, not part of the schema
+    let schema = crawl.input_schema();
+    let properties = schema["properties"].as_object().unwrap();
+    assert_eq!(properties.len(), 1);
+    assert!(properties.contains_key("pages"));
+    assert_eq!(schema["required"], serde_json::json!(["pages"]));
+
+    let args = serde_json::json!({ "pages": 3 });
+    let result = crawl.execute(args.clone(), ToolContext::default()).await.unwrap();
+    assert_eq!(result.is_error, Some(false));
+    assert_eq!(result.content[0].as_text(), Some("3"));
+
+    let token = CancellationToken::new();
+    token.cancel();
+    let result = crawl.execute(args, ToolContext::new(token)).await.unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(result.content[0].as_text(), Some("Cancelled after 0 pages"));
+
+    // A context-only method takes no arguments
+    let whoami = &tools[1];
+    let schema = whoami.input_schema();
+    assert!(schema["properties"].as_object().unwrap().is_empty());
+    let result = whoami
+        .execute(serde_json::json!({}), ToolContext::default())
+        .await
+        .unwrap();
+    assert_eq!(result.content[0].as_text(), Some("anonymous"));
+}
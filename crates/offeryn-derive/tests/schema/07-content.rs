@@ -0,0 +1,73 @@
+use offeryn_derive::tool;
+use offeryn_types::*;
+
+/// Renders charts for a dataset
+#[derive(Default)]
+struct Charts {}
+
+#[tool]
+impl Charts {
+    /// Render a bar chart as a PNG
+    ///
+    /// # Parameters
+    /// * `title` - Title drawn above the chart
+    async fn bar(&self, title: String) -> Result<Content, String> {
+        if title.is_empty() {
+            return Err("Title must not be empty".to_string());
+        }
+        Ok(Content::image("iVBORw0KGgo=", "image/png"))
+    }
+
+    /// Render a chart along with a description of it
+    async fn described(&self) -> Vec<Content> {
+        vec![
+            Content::text("Sales by month"),
+            Content::resource_link("file:///charts/sales.png", "sales.png"),
+        ]
+    }
+
+    /// Return a result the tool builds itself
+    async fn raw(&self) -> CallToolResult {
+        CallToolResult::error("Nothing to chart")
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let tools = Charts::default().tools();
+
+    let result = tools[0]
+        .execute(serde_json::json!({ "title": "Sales" }), ToolContext::default())
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+    match &result.content[0] {
+        Content::Image { data, mime_type, .. } => {
+            assert_eq!(data, "iVBORw0KGgo=");
+            assert_eq!(mime_type, "image/png");
+        }
+        _ => panic!("Expected image content"),
+    }
+
+    let result = tools[0]
+        .execute(serde_json::json!({ "title": "" }), ToolContext::default())
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(result.content[0].as_text(), Some("Title must not be empty"));
+
+    let result = tools[1]
+        .execute(serde_json::json!({}), ToolContext::default())
+        .await
+        .unwrap();
+    assert_eq!(result.content.len(), 2);
+    assert_eq!(result.content[0].as_text(), Some("Sales by month"));
+    assert!(matches!(result.content[1], Content::ResourceLink { .. }));
+
+    let result = tools[2]
+        .execute(serde_json::json!({}), ToolContext::default())
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(result.content[0].as_text(), Some("Nothing to chart"));
+}
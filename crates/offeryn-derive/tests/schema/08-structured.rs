@@ -0,0 +1,95 @@
+use offeryn_derive::tool;
+use offeryn_types::*;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+#[derive(Serialize, JsonSchema)]
+struct Forecast {
+    /// Temperature in degrees Celsius
+    temperature: f64,
+    conditions: String,
+}
+
+/// Looks up the weather
+#[derive(Default)]
+struct Weather {}
+
+#[tool]
+impl Weather {
+    /// Get the forecast for a city
+    ///
+    /// # Parameters
+    /// * `city` - City to look up
+    #[tool(structured)]
+    async fn forecast(&self, city: String) -> Result<Forecast, String> {
+        if city != "Cardiff" {
+            return Err(format!("No forecast for {}", city));
+        }
+        Ok(Forecast {
+            temperature: 12.5,
+            conditions: "Rain".to_string(),
+        })
+    }
+
+    /// List the cities with forecasts
+    #[tool(structured)]
+    async fn cities(&self) -> Vec<String> {
+        vec!["Cardiff".to_string()]
+    }
+
+    /// Count the cities with forecasts
+    async fn count(&self) -> u32 {
+        1
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let tools = Weather::default().tools();
+
+    let forecast = &tools[0];
+    let schema = forecast.output_schema().unwrap();
+    assert_eq!(schema["type"], "object");
+    assert_eq!(schema["properties"]["temperature"]["type"], "number");
+    assert_eq!(
+        schema["required"],
+        serde_json::json!(["conditions", "temperature"])
+    );
+
+    let result = forecast
+        .execute(serde_json::json!({ "city": "Cardiff" }), ToolContext::default())
+        .await
+        .unwrap();
+    let expected = serde_json::json!({ "temperature": 12.5, "conditions": "Rain" });
+    assert_eq!(result.structured_content, Some(expected.clone()));
+    // Clients without structured output support still get the JSON as text
+    let text: serde_json::Value =
+        serde_json::from_str(result.content[0].as_text().unwrap()).unwrap();
+    assert_eq!(text, expected);
+
+    let result = forecast
+        .execute(serde_json::json!({ "city": "Paris" }), ToolContext::default())
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert!(result.structured_content.is_none());
+
+    // Arrays are not valid structured content, so they are sent as JSON text
+    let cities = &tools[1];
+    assert!(cities.output_schema().is_none());
+    let result = cities
+        .execute(serde_json::json!({}), ToolContext::default())
+        .await
+        .unwrap();
+    assert!(result.structured_content.is_none());
+    assert_eq!(result.content[0].as_text(), Some(r#"["Cardiff"]"#));
+
+    // Scalars keep their plain text rendering
+    let count = &tools[2];
+    assert!(count.output_schema().is_none());
+    let result = count
+        .execute(serde_json::json!({}), ToolContext::default())
+        .await
+        .unwrap();
+    assert_eq!(result.content[0].as_text(), Some("1"));
+}
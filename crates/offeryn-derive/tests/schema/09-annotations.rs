@@ -0,0 +1,67 @@
+use offeryn_derive::tool;
+use offeryn_types::*;
+
+/// Administrative operations on user accounts
+#[derive(Default)]
+struct Admin {}
+
+#[tool]
+impl Admin {
+    /// Look up a user by id
+    #[tool(read_only, idempotent, title = "Find user")]
+    async fn find_user(&self, id: u64) -> String {
+        format!("user-{}", id)
+    }
+
+    /// Permanently delete a user
+    #[tool(destructive, idempotent = false, open_world = false)]
+    async fn delete_user(&self, id: u64) -> Result<String, String> {
+        Ok(format!("deleted user-{}", id))
+    }
+
+    /// Count users
+    async fn count(&self) -> u64 {
+        0
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let tools = Admin::default().tools();
+
+    let find = &tools[0];
+    assert_eq!(find.title(), Some("Find user"));
+    assert_eq!(
+        find.annotations(),
+        Some(ToolAnnotations {
+            title: Some("Find user".to_string()),
+            read_only_hint: Some(true),
+            idempotent_hint: Some(true),
+            ..Default::default()
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(find.annotations()).unwrap(),
+        serde_json::json!({
+            "title": "Find user",
+            "readOnlyHint": true,
+            "idempotentHint": true
+        })
+    );
+
+    let delete = &tools[1];
+    assert_eq!(delete.title(), None);
+    assert_eq!(
+        delete.annotations(),
+        Some(ToolAnnotations {
+            destructive_hint: Some(true),
+            idempotent_hint: Some(false),
+            open_world_hint: Some(false),
+            ..Default::default()
+        })
+    );
+
+    // Methods without options have no annotations
+    assert_eq!(tools[2].title(), None);
+    assert_eq!(tools[2].annotations(), None);
+}
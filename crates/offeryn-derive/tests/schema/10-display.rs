@@ -0,0 +1,56 @@
+use offeryn_derive::tool;
+use offeryn_types::*;
+use std::fmt;
+
+/// A value that can only be rendered as text
+struct Temperature(f64);
+
+impl fmt::Display for Temperature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.1}°C", self.0)
+    }
+}
+
+#[derive(Default)]
+struct Thermometer {}
+
+#[tool]
+impl Thermometer {
+    /// Read the current temperature
+    async fn read(&self) -> Temperature {
+        Temperature(21.5)
+    }
+
+    /// Read the temperature in a room
+    ///
+    /// # Parameters
+    /// * `room` - Room to read
+    async fn read_room(&self, room: String) -> Result<Temperature, String> {
+        match room.as_str() {
+            "kitchen" => Ok(Temperature(23.0)),
+            _ => Err(format!("No thermometer in {}", room)),
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let tools = Thermometer::default().tools();
+
+    // Types without `#[tool(structured)]` are rendered with Display
+    let read = &tools[0];
+    assert!(read.output_schema().is_none());
+    let result = read
+        .execute(serde_json::json!({}), ToolContext::default())
+        .await
+        .unwrap();
+    assert!(result.structured_content.is_none());
+    assert_eq!(result.content[0].as_text(), Some("21.5°C"));
+
+    let read_room = &tools[1];
+    let result = read_room
+        .execute(serde_json::json!({ "room": "kitchen" }), ToolContext::default())
+        .await
+        .unwrap();
+    assert_eq!(result.content[0].as_text(), Some("23.0°C"));
+}
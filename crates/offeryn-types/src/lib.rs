@@ -6,17 +6,166 @@ pub use jsonrpc_core::{
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::Arc;
+pub use tokio_util::sync::CancellationToken;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ToolContent {
-    pub r#type: String,
-    pub text: String,
+/// The client end of a session, used by tools to send messages back to it.
+#[async_trait]
+pub trait Peer: Send + Sync {
+    fn session_id(&self) -> &str;
+    /// The client's `clientInfo` from `initialize`, once the handshake has happened.
+    fn client_info(&self) -> Option<Implementation>;
+    fn client_capabilities(&self) -> Option<ClientCapabilities>;
+    /// The client's roots as of the last `roots/list`, or `None` if the client
+    /// doesn't support roots or hasn't answered yet.
+    fn roots(&self) -> Option<Vec<Root>>;
+    /// The protocol revision negotiated in `initialize`.
+    fn protocol_version(&self) -> &'static str;
+    async fn notify(&self, method: &str, params: Value) -> Result<(), String>;
+    /// Send a request to the client and wait for its result.
+    async fn request(&self, method: &str, params: Value) -> Result<Value, String>;
+    /// Send `notifications/message` if `level` is at or above the level the
+    /// client selected with `logging/setLevel`.
+    async fn log(
+        &self,
+        level: LoggingLevel,
+        logger: Option<&str>,
+        data: Value,
+    ) -> Result<(), String>;
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ToolResult {
-    pub content: Vec<ToolContent>,
-    pub is_error: bool,
+/// Per-call state the server hands to [`McpTool::execute`].
+#[derive(Clone, Default)]
+pub struct ToolContext {
+    cancellation: CancellationToken,
+    peer: Option<Arc<dyn Peer>>,
+    progress_token: Option<ProgressToken>,
+}
+
+impl std::fmt::Debug for ToolContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ToolContext")
+            .field("cancellation", &self.cancellation)
+            .field("progress_token", &self.progress_token)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ToolContext {
+    pub fn new(cancellation: CancellationToken) -> Self {
+        Self {
+            cancellation,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_peer(mut self, peer: Arc<dyn Peer>) -> Self {
+        self.peer = Some(peer);
+        self
+    }
+
+    pub fn with_progress_token(mut self, progress_token: Option<ProgressToken>) -> Self {
+        self.progress_token = progress_token;
+        self
+    }
+
+    /// Cancelled when the client sends `notifications/cancelled` for this call
+    /// or disconnects. The server also stops polling the call at that point, so
+    /// this matters for work that is spawned or runs between await points.
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    pub fn session_id(&self) -> Option<&str> {
+        self.peer.as_deref().map(Peer::session_id)
+    }
+
+    pub fn client_info(&self) -> Option<Implementation> {
+        self.peer.as_ref().and_then(|peer| peer.client_info())
+    }
+
+    pub fn client_capabilities(&self) -> Option<ClientCapabilities> {
+        self.peer
+            .as_ref()
+            .and_then(|peer| peer.client_capabilities())
+    }
+
+    /// The protocol revision negotiated with the client, for tools whose
+    /// output depends on what the client understands.
+    pub fn protocol_version(&self) -> Option<&'static str> {
+        self.peer.as_ref().map(|peer| peer.protocol_version())
+    }
+
+    /// The roots the client exposed to this server. Filesystem tools can use
+    /// these to stay inside the user's workspace.
+    pub fn roots(&self) -> Option<Vec<Root>> {
+        self.peer.as_ref().and_then(|peer| peer.roots())
+    }
+
+    /// Send a log message to the client. Messages below the client's selected
+    /// level are dropped.
+    pub async fn log(
+        &self,
+        level: LoggingLevel,
+        logger: Option<&str>,
+        data: Value,
+    ) -> Result<(), String> {
+        match &self.peer {
+            Some(peer) => peer.log(level, logger, data).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Ask the client to sample its language model (`sampling/createMessage`).
+    /// Fails if the client did not advertise the sampling capability.
+    pub async fn create_message(
+        &self,
+        request: CreateMessageRequest,
+    ) -> Result<CreateMessageResult, String> {
+        let Some(peer) = &self.peer else {
+            return Err("No client connected".to_string());
+        };
+        if peer
+            .client_capabilities()
+            .and_then(|capabilities| capabilities.sampling)
+            .is_none()
+        {
+            return Err("Client does not support sampling".to_string());
+        }
+        let params = serde_json::to_value(request).map_err(|e| e.to_string())?;
+        let result = peer.request("sampling/createMessage", params).await?;
+        serde_json::from_value(result).map_err(|e| e.to_string())
+    }
+
+    /// The token the client attached to this call, if it wants progress updates.
+    pub fn progress_token(&self) -> Option<&ProgressToken> {
+        self.progress_token.as_ref()
+    }
+
+    /// Send `notifications/progress` for this call. Does nothing unless the
+    /// client asked for progress by sending a `progressToken`.
+    pub async fn progress(
+        &self,
+        progress: f64,
+        total: Option<f64>,
+        message: Option<String>,
+    ) -> Result<(), String> {
+        let (Some(peer), Some(progress_token)) = (&self.peer, &self.progress_token) else {
+            return Ok(());
+        };
+        let notification = ProgressNotification {
+            progress_token: progress_token.clone(),
+            progress,
+            total,
+            message,
+        };
+        let params = serde_json::to_value(notification).map_err(|e| e.to_string())?;
+        peer.notify("notifications/progress", params).await
+    }
 }
 
 #[async_trait]
@@ -24,7 +173,17 @@ pub trait McpTool: Send + Sync {
     fn name(&self) -> &str;
     fn description(&self) -> &str;
     fn input_schema(&self) -> Value;
-    async fn execute(&self, args: Value) -> Result<ToolResult, String>;
+    /// Schema for the `structuredContent` this tool returns, if any.
+    fn output_schema(&self) -> Option<Value> {
+        None
+    }
+    fn title(&self) -> Option<&str> {
+        None
+    }
+    fn annotations(&self) -> Option<ToolAnnotations> {
+        None
+    }
+    async fn execute(&self, args: Value, ctx: ToolContext) -> Result<CallToolResult, String>;
 }
 
 pub trait HasTools {
@@ -32,26 +191,173 @@ pub trait HasTools {
     fn tools(self) -> Self::Tools;
 }
 
+/// A resource with a fixed URI that can be read by clients.
+#[async_trait]
+pub trait McpResource: Send + Sync {
+    fn uri(&self) -> &str;
+    fn name(&self) -> &str;
+    fn description(&self) -> Option<&str> {
+        None
+    }
+    fn mime_type(&self) -> Option<&str> {
+        None
+    }
+    async fn read(&self) -> Result<Vec<ResourceContents>, String>;
+}
+
+/// A family of resources addressed by an RFC 6570 URI template.
+#[async_trait]
+pub trait McpResourceTemplate: Send + Sync {
+    fn uri_template(&self) -> &str;
+    fn name(&self) -> &str;
+    fn description(&self) -> Option<&str> {
+        None
+    }
+    fn mime_type(&self) -> Option<&str> {
+        None
+    }
+    /// Whether `uri` is one of the resources described by this template.
+    fn matches(&self, uri: &str) -> bool;
+    async fn read(&self, uri: &str) -> Result<Vec<ResourceContents>, String>;
+}
+
+/// Decode `%XX` escapes in a value captured from a resource URI. Returns
+/// `None` for malformed escapes or bytes that aren't UTF-8.
+pub fn percent_decode(value: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// A resource or resource template produced by a [`HasResources`] provider.
+pub enum ResourceEntry {
+    Static(Box<dyn McpResource>),
+    Template(Box<dyn McpResourceTemplate>),
+}
+
+pub trait HasResources {
+    type Resources: IntoIterator<Item = ResourceEntry>;
+    fn resources(self) -> Self::Resources;
+}
+
+/// Conversion from the return value of a `#[resource]` method into resource contents.
+pub trait IntoResourceContents {
+    fn into_resource_contents(self, uri: &str, mime_type: Option<&str>) -> Vec<ResourceContents>;
+}
+
+impl IntoResourceContents for String {
+    fn into_resource_contents(self, uri: &str, mime_type: Option<&str>) -> Vec<ResourceContents> {
+        vec![ResourceContents::Text(TextResourceContents {
+            uri: uri.to_string(),
+            mime_type: mime_type.map(str::to_string),
+            text: self,
+        })]
+    }
+}
+
+impl IntoResourceContents for ResourceContents {
+    fn into_resource_contents(self, _uri: &str, _mime_type: Option<&str>) -> Vec<ResourceContents> {
+        vec![self]
+    }
+}
+
+impl IntoResourceContents for Vec<ResourceContents> {
+    fn into_resource_contents(self, _uri: &str, _mime_type: Option<&str>) -> Vec<ResourceContents> {
+        self
+    }
+}
+
+/// A reusable prompt template that clients can fill in and hand to a model.
+#[async_trait]
+pub trait McpPrompt: Send + Sync {
+    fn name(&self) -> &str;
+    fn description(&self) -> &str;
+    fn arguments(&self) -> Vec<PromptArgument>;
+    async fn render(&self, args: HashMap<String, String>) -> Result<Vec<PromptMessage>, String>;
+}
+
+pub trait HasPrompts {
+    type Prompts: IntoIterator<Item = Box<dyn McpPrompt>>;
+    fn prompts(self) -> Self::Prompts;
+}
+
+/// Conversion from the return value of a `#[prompt]` method into prompt messages.
+pub trait IntoPromptMessages {
+    fn into_prompt_messages(self) -> Vec<PromptMessage>;
+}
+
+impl IntoPromptMessages for Vec<PromptMessage> {
+    fn into_prompt_messages(self) -> Vec<PromptMessage> {
+        self
+    }
+}
+
+impl IntoPromptMessages for PromptMessage {
+    fn into_prompt_messages(self) -> Vec<PromptMessage> {
+        vec![self]
+    }
+}
+
+/// A bare string becomes a single user message.
+impl IntoPromptMessages for String {
+    fn into_prompt_messages(self) -> Vec<PromptMessage> {
+        vec![PromptMessage {
+            role: Role::User,
+            content: Content::text(self),
+        }]
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Implementation {
     pub name: String,
     pub version: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ClientCapabilities {
+    #[serde(default)]
     pub experimental: HashMap<String, Value>,
-    pub sampling: HashMap<String, Value>,
-    pub roots: RootsCapability,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<HashMap<String, Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roots: Option<RootsCapability>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RootsCapability {
+    #[serde(default)]
     pub list_changed: bool,
 }
 
+/// A directory or file the client has exposed to the server, usually a
+/// `file://` URI for the user's workspace.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Root {
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListRootsResult {
+    pub roots: Vec<Root>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InitializeParams {
@@ -63,7 +369,36 @@ pub struct InitializeParams {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ServerCapabilities {
+    #[serde(default)]
     pub tools: HashMap<String, bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resources: Option<ResourcesCapability>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompts: Option<PromptsCapability>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logging: Option<LoggingCapability>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoggingCapability {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptsCapability {
+    /// Whether the server notifies clients when the prompt list changes
+    #[serde(default)]
+    pub list_changed: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourcesCapability {
+    /// Whether the server supports subscribing to resource updates
+    #[serde(default)]
+    pub subscribe: bool,
+    /// Whether the server notifies clients when the resource list changes
+    #[serde(default)]
+    pub list_changed: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -92,8 +427,19 @@ pub struct InitializeResult {
     pub instructions: Option<String>,
 }
 
-pub const LATEST_PROTOCOL_VERSION: &str = "2024-11-05";
-pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2024-11-05"];
+pub const LATEST_PROTOCOL_VERSION: &str = "2025-06-18";
+/// Protocol revisions this crate speaks, newest first.
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// Pick the protocol version to answer `initialize` with: the client's own
+/// version if we support it, otherwise the newest we know.
+pub fn negotiate_protocol_version(requested: &str) -> &'static str {
+    SUPPORTED_PROTOCOL_VERSIONS
+        .iter()
+        .find(|version| **version == requested)
+        .copied()
+        .unwrap_or(LATEST_PROTOCOL_VERSION)
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -133,6 +479,40 @@ pub struct Tool {
 
     /// JSON Schema describing the tool's input parameters
     pub input_schema: Value, // Using serde_json::Value for the JSON Schema object
+
+    /// JSON Schema for the tool's `structuredContent`, if it returns any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<Value>,
+
+    /// Human-friendly name to show instead of `name`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// Hints about the tool's behaviour
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<ToolAnnotations>,
+}
+
+/// Hints clients use to decide whether to confirm a tool call with the user.
+/// They describe the tool but are not enforced; clients should not trust them
+/// from servers they don't trust.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolAnnotations {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The tool does not modify its environment (default false)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only_hint: Option<bool>,
+    /// Updates may destroy data; only meaningful when not read-only (default true)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destructive_hint: Option<bool>,
+    /// Repeating a call with the same arguments has no further effect (default false)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotent_hint: Option<bool>,
+    /// The tool talks to systems outside the server (default true)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_world_hint: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -141,28 +521,518 @@ pub struct CallToolRequest {
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub arguments: Option<std::collections::HashMap<String, Value>>,
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<RequestMeta>,
+}
+
+/// The `_meta` object clients may attach to request params.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestMeta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress_token: Option<ProgressToken>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ProgressToken {
+    Number(i64),
+    String(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressNotification {
+    pub progress_token: ProgressToken,
+    pub progress: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CallToolResult {
     pub content: Vec<Content>,
+    /// Machine-readable result matching the tool's `outputSchema`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_error: Option<bool>,
 }
 
+impl CallToolResult {
+    pub fn success(content: Vec<Content>) -> Self {
+        Self {
+            content,
+            structured_content: None,
+            is_error: Some(false),
+        }
+    }
+
+    /// A failed call. The message is shown to the model so it can recover.
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            content: vec![Content::text(message)],
+            structured_content: None,
+            is_error: Some(true),
+        }
+    }
+
+    /// A result carrying `value` as structured content, with its JSON text as
+    /// a fallback for clients that only read `content`. Only objects are
+    /// valid structured content; anything else is sent as text alone.
+    pub fn structured(value: Value) -> Self {
+        let text = value.to_string();
+        Self {
+            structured_content: value.is_object().then_some(value),
+            ..Self::text(text)
+        }
+    }
+
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::success(vec![Content::text(text)])
+    }
+}
+
+/// Conversion from the return value of a `#[tool]` method into a tool result.
+/// Other return types are rendered with `Display` as a single text block.
+pub trait IntoToolResult {
+    fn into_tool_result(self) -> CallToolResult;
+}
+
+impl IntoToolResult for CallToolResult {
+    fn into_tool_result(self) -> CallToolResult {
+        self
+    }
+}
+
+impl IntoToolResult for Content {
+    fn into_tool_result(self) -> CallToolResult {
+        CallToolResult::success(vec![self])
+    }
+}
+
+impl IntoToolResult for Vec<Content> {
+    fn into_tool_result(self) -> CallToolResult {
+        CallToolResult::success(self)
+    }
+}
+
+/// A piece of content in a tool result, prompt message or sampling message.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type")]
+#[serde(
+    tag = "type",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
 pub enum Content {
-    #[serde(rename = "text")]
-    Text { text: String },
+    Text {
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        annotations: Option<Annotations>,
+    },
 
-    #[serde(rename = "image")]
     Image {
-        url: String,
-        mime_type: Option<String>,
+        /// Base64-encoded image data
+        data: String,
+        mime_type: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        annotations: Option<Annotations>,
+    },
+
+    Audio {
+        /// Base64-encoded audio data
+        data: String,
+        mime_type: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        annotations: Option<Annotations>,
     },
 
+    /// The contents of a resource, inlined into the message.
     #[serde(rename = "resource")]
-    EmbeddedResource { uri: String, name: Option<String> },
+    EmbeddedResource {
+        resource: ResourceContents,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        annotations: Option<Annotations>,
+    },
+
+    /// A reference to a resource the client can read separately.
+    ResourceLink {
+        uri: String,
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mime_type: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        annotations: Option<Annotations>,
+    },
+}
+
+impl Content {
+    pub fn text(text: impl Into<String>) -> Self {
+        Content::Text {
+            text: text.into(),
+            annotations: None,
+        }
+    }
+
+    pub fn image(data: impl Into<String>, mime_type: impl Into<String>) -> Self {
+        Content::Image {
+            data: data.into(),
+            mime_type: mime_type.into(),
+            annotations: None,
+        }
+    }
+
+    pub fn audio(data: impl Into<String>, mime_type: impl Into<String>) -> Self {
+        Content::Audio {
+            data: data.into(),
+            mime_type: mime_type.into(),
+            annotations: None,
+        }
+    }
+
+    pub fn resource(resource: ResourceContents) -> Self {
+        Content::EmbeddedResource {
+            resource,
+            annotations: None,
+        }
+    }
+
+    pub fn resource_link(uri: impl Into<String>, name: impl Into<String>) -> Self {
+        Content::ResourceLink {
+            uri: uri.into(),
+            name: name.into(),
+            description: None,
+            mime_type: None,
+            annotations: None,
+        }
+    }
+
+    /// The text of a text block.
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Content::Text { text, .. } => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Attach annotations, replacing any already set.
+    pub fn with_annotations(mut self, value: Annotations) -> Self {
+        match &mut self {
+            Content::Text { annotations, .. }
+            | Content::Image { annotations, .. }
+            | Content::Audio { annotations, .. }
+            | Content::EmbeddedResource { annotations, .. }
+            | Content::ResourceLink { annotations, .. } => *annotations = Some(value),
+        }
+        self
+    }
+
+    pub fn annotations(&self) -> Option<&Annotations> {
+        match self {
+            Content::Text { annotations, .. }
+            | Content::Image { annotations, .. }
+            | Content::Audio { annotations, .. }
+            | Content::EmbeddedResource { annotations, .. }
+            | Content::ResourceLink { annotations, .. } => annotations.as_ref(),
+        }
+    }
+}
+
+/// Hints to the client about who a piece of content is for and how much it matters.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Annotations {
+    /// Who the content is intended for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audience: Option<Vec<Role>>,
+    /// Importance from 0 (optional) to 1 (required)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Resource {
+    /// URI identifying the resource
+    pub uri: String,
+
+    /// Human-readable name of the resource
+    pub name: String,
+
+    /// Description of what the resource contains
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// MIME type of the resource contents, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceTemplate {
+    /// RFC 6570 URI template used to construct resource URIs
+    pub uri_template: String,
+
+    /// Human-readable name for resources of this type
+    pub name: String,
+
+    /// Description of what resources of this type contain
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// MIME type shared by all resources matching the template, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListResourcesResult {
+    /// Array of available resources
+    pub resources: Vec<Resource>,
+
+    /// Optional pagination cursor for getting the next page of results
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListResourceTemplatesResult {
+    /// Array of available resource templates
+    pub resource_templates: Vec<ResourceTemplate>,
+
+    /// Optional pagination cursor for getting the next page of results
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadResourceRequest {
+    pub uri: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscribeRequest {
+    pub uri: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceUpdatedNotification {
+    pub uri: String,
+}
+
+/// Syslog severities used by `logging/setLevel` and `notifications/message`,
+/// ordered from least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LoggingLevel {
+    Debug,
+    Info,
+    Notice,
+    Warning,
+    Error,
+    Critical,
+    Alert,
+    Emergency,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetLevelRequest {
+    pub level: LoggingLevel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoggingMessageNotification {
+    pub level: LoggingLevel,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logger: Option<String>,
+    pub data: Value,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMessageRequest {
+    pub messages: Vec<SamplingMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_preferences: Option<ModelPreferences>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_context: Option<IncludeContext>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    pub max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SamplingMessage {
+    pub role: Role,
+    pub content: Content,
+}
+
+/// Hints for the client when it picks a model. Priorities range from 0 to 1.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPreferences {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hints: Option<Vec<ModelHint>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_priority: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed_priority: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intelligence_priority: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelHint {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IncludeContext {
+    None,
+    ThisServer,
+    AllServers,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMessageResult {
+    pub role: Role,
+    pub content: Content,
+    pub model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelledNotification {
+    pub request_id: Id,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadResourceResult {
+    pub contents: Vec<ResourceContents>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ResourceContents {
+    Text(TextResourceContents),
+    Blob(BlobResourceContents),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextResourceContents {
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobResourceContents {
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    /// Base64-encoded binary data
+    pub blob: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Prompt {
+    /// Name of the prompt
+    pub name: String,
+
+    /// Description of what the prompt is for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Arguments used to fill in the prompt template
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arguments: Vec<PromptArgument>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptArgument {
+    /// Name of the argument
+    pub name: String,
+
+    /// Description of what the argument is used for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Whether the argument must be provided
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListPromptsResult {
+    /// Array of available prompts
+    pub prompts: Vec<Prompt>,
+
+    /// Optional pagination cursor for getting the next page of results
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPromptRequest {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPromptResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub messages: Vec<PromptMessage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptMessage {
+    pub role: Role,
+    pub content: Content,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Assistant,
 }
@@ -0,0 +1,39 @@
+use offeryn::types::{Content, Implementation};
+use offeryn::{ChildProcessTransport, McpClient};
+use serde_json::json;
+use tokio::process::Command;
+
+#[tokio::test]
+async fn test_drive_calculator_over_stdio() {
+    let transport =
+        ChildProcessTransport::spawn(Command::new(env!("CARGO_BIN_EXE_stdio-calculator"))).unwrap();
+    assert!(transport.id().is_some());
+    let client = McpClient::new(transport);
+
+    let result = client
+        .initialize(Implementation {
+            name: "stdio-calculator-test".to_string(),
+            version: "0.1.0".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(result.server_info.name, "calculator");
+
+    let tools = client.list_tools().await.unwrap().tools;
+    assert_eq!(tools.len(), 4);
+
+    let result = client
+        .call_tool("calculator_multiply", json!({ "a": 4.0, "b": 5.0 }))
+        .await
+        .unwrap();
+    match &result.content[0] {
+        Content::Text { text, .. } => assert_eq!(text, "20"),
+        _ => panic!("Expected text content"),
+    }
+
+    let result = client
+        .call_tool("calculator_divide", json!({ "a": 1.0, "b": 0.0 }))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+}
@@ -1,8 +1,15 @@
-pub use offeryn_core::{transport::SseTransport, transport::StdioTransport, McpServer};
-pub use offeryn_derive::tool;
+pub use offeryn_core::{
+    transport::SseTransport, transport::StdioTransport, transport::StreamableHttpTransport,
+    transport::WebSocketTransport, McpLoggingLayer, McpServer,
+};
+#[cfg(feature = "client")]
+pub use offeryn_core::{ChildProcessTransport, ClientTransport, McpClient, SseClientTransport};
+pub use offeryn_derive::{prompt, resource, tool};
 pub use offeryn_types as types;
 
 pub mod prelude {
+    pub use crate::prompt as mcp_prompt;
+    pub use crate::resource as mcp_resource;
     pub use crate::tool as mcp_tool;
     pub use offeryn_types;
     pub use schemars;